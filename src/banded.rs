@@ -0,0 +1,334 @@
+use crate::linear_operator::LinearOperator;
+use crate::mat::Mat;
+use crate::mat_eqn_solver::MatEqnSolver;
+use crate::vector::Vector;
+
+impl Mat {
+    // lower and upper bandwidth of the matrix, the number of
+    // non-zero diagonals below and above the main diagonal
+    pub fn bandwidth(&self) -> (usize, usize) {
+        let mut kl = 0;
+        let mut ku = 0;
+        for (r, row) in self.iter().enumerate() {
+            for (c, v) in row.iter().enumerate() {
+                if *v == 0.0 {
+                    continue;
+                }
+                if r > c {
+                    kl = kl.max(r - c);
+                } else {
+                    ku = ku.max(c - r);
+                }
+            }
+        }
+        (kl, ku)
+    }
+}
+
+#[derive(Debug, Clone)]
+// tridiagonal matrix, stored as its three diagonals
+pub struct Tridiagonal {
+    sub: Vec<f64>,
+    diag: Vec<f64>,
+    sup: Vec<f64>,
+}
+
+impl Tridiagonal {
+    // construct a new tridiagonal matrix from its sub-diagonal (length n - 1),
+    // main diagonal (length n) and super-diagonal (length n - 1)
+    pub fn new(sub: Vec<f64>, diag: Vec<f64>, sup: Vec<f64>) -> Self {
+        assert!(!diag.is_empty());
+        assert_eq!(sub.len() + 1, diag.len());
+        assert_eq!(sup.len() + 1, diag.len());
+        Tridiagonal { sub, diag, sup }
+    }
+
+    // size of the matrix
+    pub fn size(&self) -> usize {
+        self.diag.len()
+    }
+
+    // get a value from the matrix
+    pub fn get(&self, row: usize, col: usize) -> f64 {
+        if row == col {
+            self.diag[row]
+        } else if row == col + 1 {
+            self.sub[col]
+        } else if col == row + 1 {
+            self.sup[row]
+        } else {
+            0.0
+        }
+    }
+
+    // solve Ax = d with the thomas algorithm, O(n).
+    // no pivoting is performed, so this is only stable for
+    // diagonally dominant or symmetric positive definite matrices
+    pub fn solve(&self, d: &[f64]) -> Result<Vec<f64>, ()> {
+        let n = self.size();
+        assert_eq!(n, d.len());
+        // forward sweep, eliminating the sub-diagonal
+        let mut c = vec![0.0; n];
+        let mut dp = vec![0.0; n];
+        for i in 0..n {
+            let (a, c_prev, d_prev) = if i == 0 {
+                (0.0, 0.0, 0.0)
+            } else {
+                (self.sub[i - 1], c[i - 1], dp[i - 1])
+            };
+            let m = self.diag[i] - a * c_prev;
+            if m == 0.0 {
+                return Err(());
+            }
+            if i + 1 < n {
+                c[i] = self.sup[i] / m;
+            }
+            dp[i] = (d[i] - a * d_prev) / m;
+        }
+        // back substitution
+        let mut x = dp;
+        for i in (0..(n - 1)).rev() {
+            x[i] -= c[i] * x[i + 1];
+        }
+        Ok(x)
+    }
+}
+
+impl LinearOperator for Tridiagonal {
+    fn rows(&self) -> usize {
+        self.size()
+    }
+
+    fn cols(&self) -> usize {
+        self.size()
+    }
+
+    fn mul_vec(&self, x: &[f64]) -> Vector {
+        assert_eq!(self.size(), x.len());
+        (0..self.size()).map(|r| self.row_dot(r, x)).collect()
+    }
+
+    fn row_dot(&self, r: usize, x: &[f64]) -> f64 {
+        let mut res = self.diag[r] * x[r];
+        if r > 0 {
+            res += self.sub[r - 1] * x[r - 1];
+        }
+        if r + 1 < self.size() {
+            res += self.sup[r] * x[r + 1];
+        }
+        res
+    }
+
+    fn diag(&self, i: usize) -> f64 {
+        self.diag[i]
+    }
+}
+
+impl From<&Tridiagonal> for Mat {
+    fn from(t: &Tridiagonal) -> Self {
+        Mat::from(&Banded::from(t))
+    }
+}
+
+#[derive(Debug, Clone)]
+// square banded matrix with kl diagonals below and ku above the main
+// diagonal. row r stores columns r - kl ..= r + ku, so only
+// n * (kl + ku + 1) values are kept
+pub struct Banded {
+    n: usize,
+    kl: usize,
+    ku: usize,
+    data: Box<[f64]>,
+}
+
+impl Banded {
+    // construct a new zero banded matrix
+    pub fn new(n: usize, kl: usize, ku: usize) -> Self {
+        assert!(n > 0);
+        Banded {
+            n,
+            kl,
+            ku,
+            data: vec![0.0; n * (kl + ku + 1)].into_boxed_slice(),
+        }
+    }
+
+    // size of the matrix
+    pub fn size(&self) -> usize {
+        self.n
+    }
+
+    // lower and upper bandwidth of the matrix
+    pub fn bandwidth(&self) -> (usize, usize) {
+        (self.kl, self.ku)
+    }
+
+    // is (row, col) inside the band?
+    fn in_band(&self, row: usize, col: usize) -> bool {
+        row < self.n && col < self.n && row <= col + self.kl && col <= row + self.ku
+    }
+
+    // get a value from the matrix
+    pub fn get(&self, row: usize, col: usize) -> f64 {
+        if self.in_band(row, col) {
+            self.data[row * (self.kl + self.ku + 1) + col + self.kl - row]
+        } else {
+            0.0
+        }
+    }
+
+    // set a value in the matrix, panics if (row, col) is outside the band
+    pub fn set(&mut self, row: usize, col: usize, val: f64) {
+        assert!(self.in_band(row, col));
+        self.data[row * (self.kl + self.ku + 1) + col + self.kl - row] = val;
+    }
+
+    // columns of a row that are inside the band
+    fn band_cols(&self, row: usize) -> std::ops::Range<usize> {
+        row.saturating_sub(self.kl)..usize::min(row + self.ku + 1, self.n)
+    }
+
+    // LU decomposition without pivoting. L has the same lower bandwidth
+    // and U the same upper bandwidth as the matrix, so no fill-in occurs
+    // and the cost is O(n * kl * ku) instead of O(n^3)
+    pub fn lu(&self) -> Result<BandedLu, ()> {
+        let mut lu = self.clone();
+        let n = self.n;
+        for k in 0..n {
+            let pivot = lu.get(k, k);
+            if pivot == 0.0 {
+                return Err(());
+            }
+            for i in (k + 1)..usize::min(k + self.kl + 1, n) {
+                let l = lu.get(i, k) / pivot;
+                lu.set(i, k, l);
+                for j in (k + 1)..usize::min(k + self.ku + 1, n) {
+                    lu.set(i, j, lu.get(i, j) - l * lu.get(k, j));
+                }
+            }
+        }
+        Ok(BandedLu { lu })
+    }
+}
+
+impl LinearOperator for Banded {
+    fn rows(&self) -> usize {
+        self.n
+    }
+
+    fn cols(&self) -> usize {
+        self.n
+    }
+
+    fn mul_vec(&self, x: &[f64]) -> Vector {
+        assert_eq!(self.n, x.len());
+        (0..self.n).map(|r| self.row_dot(r, x)).collect()
+    }
+
+    fn row_dot(&self, r: usize, x: &[f64]) -> f64 {
+        self.band_cols(r).map(|c| self.get(r, c) * x[c]).sum()
+    }
+
+    fn diag(&self, i: usize) -> f64 {
+        self.get(i, i)
+    }
+}
+
+#[derive(Debug, Clone)]
+// LU decomposition of a banded matrix, with the unit diagonal
+// L and U stored together in one banded matrix
+pub struct BandedLu {
+    lu: Banded,
+}
+
+impl BandedLu {
+    // solve LUx = b with banded forward and back substitution
+    pub fn solve(&self, b: &[f64]) -> Vec<f64> {
+        let n = self.lu.n;
+        assert_eq!(n, b.len());
+        let mut y = vec![0.0; n];
+        for i in 0..n {
+            let s: f64 = (i.saturating_sub(self.lu.kl)..i)
+                .map(|j| self.lu.get(i, j) * y[j])
+                .sum();
+            y[i] = b[i] - s;
+        }
+        let mut x = vec![0.0; n];
+        for i in (0..n).rev() {
+            let s: f64 = ((i + 1)..usize::min(i + self.lu.ku + 1, n))
+                .map(|j| self.lu.get(i, j) * x[j])
+                .sum();
+            x[i] = (y[i] - s) / self.lu.get(i, i);
+        }
+        x
+    }
+}
+
+impl From<&Mat> for Banded {
+    fn from(m: &Mat) -> Self {
+        assert!(m.is_square());
+        let (kl, ku) = m.bandwidth();
+        let mut res = Banded::new(m.rows(), kl, ku);
+        for r in 0..m.rows() {
+            for c in res.band_cols(r) {
+                res.set(r, c, m.get(r, c));
+            }
+        }
+        res
+    }
+}
+
+impl From<&Banded> for Mat {
+    fn from(b: &Banded) -> Self {
+        let mut res = Mat::new(b.n, b.n);
+        for r in 0..b.n {
+            for c in b.band_cols(r) {
+                res.set(r, c, b.get(r, c));
+            }
+        }
+        res
+    }
+}
+
+impl From<&Tridiagonal> for Banded {
+    fn from(t: &Tridiagonal) -> Self {
+        let n = t.size();
+        let mut res = Banded::new(n, 1, 1);
+        for r in 0..n {
+            for c in res.band_cols(r) {
+                res.set(r, c, t.get(r, c));
+            }
+        }
+        res
+    }
+}
+
+// extract the three diagonals of the matrix, then use the thomas
+// algorithm. fails if the matrix is not tridiagonal
+pub struct Thomas;
+
+impl MatEqnSolver for Thomas {
+    fn solve(m: Mat, b: Vec<f64>) -> Result<Vec<f64>, ()> {
+        assert!(m.is_square());
+        let (kl, ku) = m.bandwidth();
+        if kl > 1 || ku > 1 {
+            return Err(());
+        }
+        let n = m.rows();
+        let t = Tridiagonal::new(
+            (1..n).map(|i| m.get(i, i - 1)).collect(),
+            (0..n).map(|i| m.get(i, i)).collect(),
+            (1..n).map(|i| m.get(i - 1, i)).collect(),
+        );
+        t.solve(&b)
+    }
+}
+
+// convert the matrix to banded storage, then solve it with banded LU
+pub struct BandedLuSolver;
+
+impl MatEqnSolver for BandedLuSolver {
+    fn solve(m: Mat, b: Vec<f64>) -> Result<Vec<f64>, ()> {
+        Ok(Banded::from(&m).lu()?.solve(&b))
+    }
+}
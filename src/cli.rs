@@ -0,0 +1,388 @@
+use math166_assignment2::cond::{CondEst, Hager};
+use math166_assignment2::eigenvalue::{power_method, EigenSolve, InversePowerMethod, QrAlgorithm};
+use math166_assignment2::format::NumFormat;
+use math166_assignment2::invert::{
+    AugmentedMat, GaussJordan, Invert, PivotedLuInvert, PseudoInverse,
+};
+use math166_assignment2::io::{self, ReadError};
+use math166_assignment2::lu_dec::{PivotedDoolittle, PivotedLuDec};
+use math166_assignment2::mat::Mat;
+use math166_assignment2::mat_eqn_solver::{MatEqnSolver, PivotedLuSolver, ReverseSub};
+use math166_assignment2::norm::{LInf, L1};
+use math166_assignment2::npy;
+use math166_assignment2::qr_dec::Householder;
+use math166_assignment2::reduce_upper::BasicReduceUpper;
+use math166_assignment2::svd::OneSidedJacobi;
+use math166_assignment2::sym_mat::SymMat;
+use math166_assignment2::upper_triangle::{Gaussian, PartialPivot, UpperTriangle};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{stdin, stdout, BufRead, BufReader, Cursor, Read};
+
+pub const USAGE: &str = "\
+usage: math166-assignment2 <command> [options] [file ...]
+
+reads the matrix from file, or from stdin if no file or '-' is given.
+with no command, runs the assignment problems.
+
+commands:
+  lu      LU decomposition, printing L and U, and P when pivoting
+  invert  inverse of the matrix
+  solve   solve Ax = b, reading b from a second file,
+          or from the last column of A if there is only one
+  eigen   eigenvalues of the matrix
+  cond    condition number of the matrix
+  det     determinant of the matrix
+
+options:
+  --pivot none|partial          row pivoting during elimination (default none)
+  --solver lu|reverse-sub       method for solve (default lu)
+  --method augmented|gauss-jordan|lu|pseudo
+                                method for invert (default augmented)
+  --method qr|jacobi|power|inverse
+                                method for eigen (default jacobi for symmetric
+                                matrices, otherwise qr, which fails when
+                                eigenvalues share a magnitude)
+  --shift Q                     find the eigenvalue closest to Q (eigen --method inverse)
+  --tol T                       accuracy for eigen (default 1e-10)
+  --norm 1|2|inf                norm for cond (default inf)
+  --estimate                    estimate the 1-norm cond from LU, without inverting,
+                                cannot be combined with --norm 2|inf
+  --input mtx|npy|csv|tsv|txt   input format (default from the file extension,
+                                or guessed from the data on stdin)
+  --format text|latex|markdown|csv|mtx
+                                output format (default text)
+  --precision N                 significant digits to print (default 6)
+";
+
+// options that take a value
+const VALUE_OPTIONS: &[&str] = &[
+    "pivot",
+    "solver",
+    "method",
+    "shift",
+    "tol",
+    "norm",
+    "input",
+    "format",
+    "precision",
+];
+
+// parsed command line
+struct Args {
+    command: String,
+    options: HashMap<String, String>,
+    estimate: bool,
+    files: Vec<String>,
+}
+
+impl Args {
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let mut iter = args.iter();
+        let command = iter.next().ok_or("no command given")?.clone();
+        let mut res = Args {
+            command,
+            options: HashMap::new(),
+            estimate: false,
+            files: Vec::new(),
+        };
+        while let Some(arg) = iter.next() {
+            if arg == "--estimate" {
+                res.estimate = true;
+            } else if let Some(name) = arg.strip_prefix("--") {
+                if !VALUE_OPTIONS.contains(&name) {
+                    return Err(format!("unknown option {}", arg));
+                }
+                let value = iter
+                    .next()
+                    .ok_or_else(|| format!("{} needs a value", arg))?;
+                res.options.insert(name.to_string(), value.clone());
+            } else {
+                res.files.push(arg.clone());
+            }
+        }
+        Ok(res)
+    }
+
+    // the value of an option, or its default
+    fn get<'a>(&'a self, name: &str, default: &'a str) -> &'a str {
+        self.options
+            .get(name)
+            .map(|s| s.as_str())
+            .unwrap_or(default)
+    }
+
+    // the value of an option parsed as a number, or its default
+    fn num<T: std::str::FromStr>(&self, name: &str, default: T) -> Result<T, String> {
+        match self.options.get(name) {
+            Some(v) => v
+                .parse()
+                .map_err(|_| format!("invalid value {:?} for --{}", v, name)),
+            None => Ok(default),
+        }
+    }
+}
+
+// read a matrix from a file, or stdin for "-"
+fn read_mat(path: &str, input: Option<&str>) -> Result<Mat, String> {
+    let err = |e: ReadError| format!("{}: {}", path, e);
+    if path != "-" && input.is_none() {
+        // the extension picks the format
+        return io::read_file(path).map_err(err);
+    }
+    let mut data = Vec::new();
+    if path == "-" {
+        stdin()
+            .read_to_end(&mut data)
+            .map_err(|e| format!("stdin: {}", e))?;
+    } else {
+        File::open(path)
+            .and_then(|mut f| f.read_to_end(&mut data))
+            .map_err(|e| format!("{}: {}", path, e))?;
+    }
+    let format = match input {
+        Some(f) => f.to_string(),
+        None => sniff(&data),
+    };
+    let r = BufReader::new(Cursor::new(data));
+    match format.as_str() {
+        "mtx" => io::read_matrix_market(r).map_err(err),
+        "npy" => npy::read_npy(r).map_err(err),
+        "csv" => io::read_delimited(r, ',').map_err(err),
+        "tsv" => io::read_delimited(r, '\t').map_err(err),
+        "txt" => io::read_whitespace(r).map_err(err),
+        f => Err(format!("unknown input format {:?}", f)),
+    }
+}
+
+// guess the format of stdin, which has no extension, from the data itself
+fn sniff(data: &[u8]) -> String {
+    if data.starts_with(b"\x93NUMPY") {
+        "npy"
+    } else if data.starts_with(b"%%MatrixMarket") {
+        "mtx"
+    } else if data
+        .lines()
+        .any(|l| l.map(|l| l.contains(',')).unwrap_or(false))
+    {
+        "csv"
+    } else {
+        "txt"
+    }
+    .to_string()
+}
+
+// how results are printed
+struct Output {
+    format: String,
+    precision: usize,
+}
+
+impl Output {
+    fn num_format(&self) -> NumFormat {
+        NumFormat::significant(self.precision)
+    }
+
+    // print a named matrix
+    fn mat(&self, name: &str, m: &Mat) -> Result<(), String> {
+        match self.format.as_str() {
+            "text" => println!("{}\n{:.*}", name, self.precision, m),
+            "latex" => println!("{} = {}\n", name, m.to_latex(self.num_format())),
+            "markdown" => println!("**{}**\n\n{}", name, m.to_markdown(self.num_format())),
+            "csv" => {
+                println!("# {}", name);
+                io::write_delimited(m, stdout(), ',').map_err(|e| e.to_string())?;
+            }
+            "mtx" => io::write_matrix_market(m, stdout()).map_err(|e| e.to_string())?,
+            f => return Err(format!("unknown output format {:?}", f)),
+        }
+        Ok(())
+    }
+
+    // print a vector, as a column
+    fn vec(&self, name: &str, v: &[f64]) -> Result<(), String> {
+        let mut m = Mat::new(v.len(), 1);
+        for (i, x) in v.iter().enumerate() {
+            m.set(i, 0, *x);
+        }
+        self.mat(name, &m)
+    }
+
+    // print a named number
+    fn scalar(&self, name: &str, v: f64) -> Result<(), String> {
+        match self.format.as_str() {
+            "text" | "markdown" => println!("{} = {}", name, self.num_format().format(v)),
+            "latex" => println!("{} = {}", name, self.num_format().format_latex(v)),
+            "csv" | "mtx" => println!("{}", v),
+            f => return Err(format!("unknown output format {:?}", f)),
+        }
+        Ok(())
+    }
+}
+
+// run the command line interface
+pub fn run(args: &[String]) -> Result<(), String> {
+    let args = Args::parse(args)?;
+    // check the command before waiting on stdin for a matrix
+    const COMMANDS: &[&str] = &["lu", "invert", "solve", "eigen", "cond", "det"];
+    if !COMMANDS.contains(&args.command.as_str()) {
+        return Err(format!("unknown command {:?}", args.command));
+    }
+    let input = args.options.get("input").map(|s| s.as_str());
+    let files: Vec<&str> = if args.files.is_empty() {
+        vec!["-"]
+    } else {
+        args.files.iter().map(|s| s.as_str()).collect()
+    };
+    let out = Output {
+        format: args.get("format", "text").to_string(),
+        precision: args.num("precision", 6)?,
+    };
+    let m = read_mat(files[0], input)?;
+    if args.command != "solve" && !m.is_square() {
+        return Err(format!(
+            "{} needs a square matrix, found {} x {}",
+            args.command,
+            m.rows(),
+            m.cols()
+        ));
+    }
+
+    let pivot = args.get("pivot", "none");
+    match pivot {
+        "none" => dispatch::<Gaussian>(&args, &out, m, &files),
+        "partial" => dispatch::<PartialPivot>(&args, &out, m, &files),
+        p => Err(format!("unknown pivoting {:?}", p)),
+    }
+}
+
+// run the command, eliminating with T
+fn dispatch<T: UpperTriangle>(
+    args: &Args,
+    out: &Output,
+    m: Mat,
+    files: &[&str],
+) -> Result<(), String> {
+    match args.command.as_str() {
+        "lu" => {
+            // even without pivoting, a zero pivot forces a swap
+            let (perm, l, u) = PivotedDoolittle::<T>::dec(m).map_err(|_| "matrix is singular")?;
+            if perm.iter().enumerate().any(|(r, c)| r != *c) {
+                let mut p = Mat::new(perm.len(), perm.len());
+                for (r, c) in perm.iter().enumerate() {
+                    p.set(r, *c, 1.0);
+                }
+                out.mat("P", &p)?;
+            }
+            out.mat("L", &Mat::from(&l))?;
+            out.mat("U", &Mat::from(&u))
+        }
+        "invert" => {
+            let singular = |_| "matrix is singular";
+            let method = args.get("method", "augmented");
+            let inv = match method {
+                "augmented" => AugmentedMat::<T, BasicReduceUpper>::invert(m).map_err(singular),
+                "gauss-jordan" => GaussJordan::invert(m).map_err(singular),
+                "lu" => PivotedLuInvert::<T>::invert(m).map_err(singular),
+                // every matrix has a pseudoinverse, only the SVD can fail
+                "pseudo" => PseudoInverse::<OneSidedJacobi>::invert(m)
+                    .map_err(|_| "singular values did not converge"),
+                s => return Err(format!("unknown invert method {:?}", s)),
+            }?;
+            out.mat(if method == "pseudo" { "A^+" } else { "A^-1" }, &inv)
+        }
+        "solve" => {
+            let (a, b) = match files.get(1) {
+                Some(path) => {
+                    let b = read_mat(path, args.options.get("input").map(|s| s.as_str()))?;
+                    let b: Vec<f64> = b.iter().flat_map(|row| row.iter().cloned()).collect();
+                    (m, b)
+                }
+                None => split_augmented(&m)?,
+            };
+            if !a.is_square() || a.rows() != b.len() {
+                return Err(format!(
+                    "cannot solve a {} x {} system with {} right hand side values",
+                    a.rows(),
+                    a.cols(),
+                    b.len()
+                ));
+            }
+            let x = match args.get("solver", "lu") {
+                "lu" => PivotedLuSolver::<T>::solve(a, b),
+                "reverse-sub" => ReverseSub::<T>::solve(a, b),
+                s => return Err(format!("unknown solver {:?}", s)),
+            }
+            .map_err(|_| "matrix is singular")?;
+            out.vec("x", &x)
+        }
+        "eigen" => {
+            let tol = args.num("tol", 1e-10)?;
+            let failed = |_| "eigenvalues did not converge".to_string();
+            // unshifted QR cannot separate eigenvalues of equal magnitude,
+            // e.g. 1 and -1, but jacobi handles any symmetric matrix
+            let sym = SymMat::from_mat(&m).is_ok();
+            match args.get("method", if sym { "jacobi" } else { "qr" }) {
+                "qr" => {
+                    let es = QrAlgorithm::<Householder>::eigenvalues(&m, tol).map_err(failed)?;
+                    out.vec("eigenvalues", &es)
+                }
+                "jacobi" => {
+                    let s = SymMat::from_mat(&m).map_err(|_| "matrix is not symmetric")?;
+                    out.vec("eigenvalues", &s.eigenvalues(tol).map_err(failed)?)
+                }
+                "power" => out.scalar("lambda", power_method(&m, tol).map_err(failed)?),
+                "inverse" => {
+                    let q = args.num("shift", 0.0)?;
+                    let e = InversePowerMethod::<ReverseSub<T>>::eigen_solve(&m, q, tol)
+                        .map_err(failed)?;
+                    out.scalar("lambda", e)
+                }
+                s => Err(format!("unknown eigenvalue method {:?}", s)),
+            }
+        }
+        "cond" => {
+            let singular = |_| "matrix is singular".to_string();
+            let k = if args.estimate {
+                if args.get("norm", "1") != "1" {
+                    return Err("--estimate always uses the 1-norm".to_string());
+                }
+                // permuting the rows of A permutes the columns of A^-1,
+                // so the 1-norms of PA and its inverse match those of A
+                let (_, l, u) = PivotedDoolittle::<T>::dec(m.clone()).map_err(singular)?;
+                Hager::cond_est(&m, &l, &u)
+            } else {
+                match args.get("norm", "inf") {
+                    "1" => m.k::<L1>(&GaussJordan::invert(m.clone()).map_err(singular)?),
+                    "inf" => m.k::<LInf>(&GaussJordan::invert(m.clone()).map_err(singular)?),
+                    "2" => m
+                        .cond2::<OneSidedJacobi>()
+                        .map_err(|_| "singular values did not converge")?,
+                    n => return Err(format!("unknown norm {:?}", n)),
+                }
+            };
+            out.scalar("K(A)", k)
+        }
+        "det" => out.scalar("det(A)", m.det::<T>()),
+        _ => unreachable!(),
+    }
+}
+
+// split an augmented matrix [A | b] into A and b
+fn split_augmented(m: &Mat) -> Result<(Mat, Vec<f64>), String> {
+    if m.cols() != m.rows() + 1 {
+        return Err(format!(
+            "expected an augmented n x (n + 1) matrix, found {} x {}",
+            m.rows(),
+            m.cols()
+        ));
+    }
+    let n = m.rows();
+    let mut a = Mat::new(n, n);
+    for r in 0..n {
+        for c in 0..n {
+            a.set(r, c, m.get(r, c));
+        }
+    }
+    Ok((a, (0..n).map(|r| m.get(r, n)).collect()))
+}
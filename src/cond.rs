@@ -0,0 +1,85 @@
+use crate::eigenvalue::find_max_mag;
+use crate::lu_dec::LuDec;
+use crate::mat::Mat;
+use crate::norm::{LInf, Norm, VecNorm, L1};
+use crate::triangular::{LowerTriangular, UpperTriangular};
+use std::marker::PhantomData;
+
+// trait representing a method of estimating the 1-norm condition number
+// of a matrix A, given its LU decomposition A = LU
+pub trait CondEst {
+    fn cond_est(m: &Mat, l: &LowerTriangular, u: &UpperTriangular) -> f64;
+}
+
+// hager's method for estimating ||A^-1||_1, with higham's refinements:
+// iterate on the dual problem at most MAX_ITER times, then compare against
+// an alternating sign vector to catch matrices hager's iteration misses
+pub struct Hager;
+
+impl CondEst for Hager {
+    fn cond_est(m: &Mat, l: &LowerTriangular, u: &UpperTriangular) -> f64 {
+        assert!(m.is_square());
+        // a zero on the diagonal means A is singular
+        L1::norm(m) * hager_inv_norm(l, u).unwrap_or(1. / 0.)
+    }
+}
+
+// estimate ||A^-1||_1, where A = LU
+fn hager_inv_norm(l: &LowerTriangular, u: &UpperTriangular) -> Result<f64, ()> {
+    const MAX_ITER: usize = 5;
+
+    let n = l.size();
+    let lt = l.transpose();
+    let ut = u.transpose();
+    // A^-1 x = U^-1 L^-1 x
+    let solve = |x: &[f64]| u.solve(&l.solve(x)?);
+    // A^-T x = L^-T U^-T x
+    let solve_t = |x: &[f64]| lt.solve(&ut.solve(x)?);
+
+    let mut x = vec![1. / n as f64; n];
+    let mut est = 0.;
+    let mut prev_j = None;
+    for _ in 0..MAX_ITER {
+        let y = solve(&x)?;
+        est = L1::vec_norm(&y);
+
+        let xi: Vec<f64> = y.iter().map(|v| if *v >= 0. { 1. } else { -1. }).collect();
+        let z = solve_t(&xi)?;
+
+        let j = find_max_mag(&z);
+        let ztx: f64 = z.iter().zip(x.iter()).map(|(v1, v2)| *v1 * *v2).sum();
+        if LInf::vec_norm(&z) <= ztx || prev_j == Some(j) {
+            break;
+        }
+        x = vec![0.; n];
+        x[j] = 1.;
+        prev_j = Some(j);
+    }
+
+    // higham's alternating sign safeguard
+    if n > 1 {
+        let b: Vec<f64> = (0..n)
+            .map(|i| {
+                let sign = if i % 2 == 0 { 1. } else { -1. };
+                sign * (1. + i as f64 / (n - 1) as f64)
+            })
+            .collect();
+        let alt = 2. * L1::vec_norm(&solve(&b)?) / (3. * n as f64);
+        est = f64::max(est, alt);
+    }
+    Ok(est)
+}
+
+// estimate the condition number of a matrix, computing
+// its LU decomposition with D and then estimating with C
+pub struct LuCondEst<C: CondEst, D: LuDec> {
+    c: PhantomData<*const C>,
+    d: PhantomData<*const D>,
+}
+
+impl<C: CondEst, D: LuDec> LuCondEst<C, D> {
+    pub fn estimate(m: &Mat) -> Result<f64, ()> {
+        let (l, u) = D::dec(m.clone())?;
+        Ok(C::cond_est(m, &l, &u))
+    }
+}
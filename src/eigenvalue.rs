@@ -1,5 +1,7 @@
+use crate::linear_operator::LinearOperator;
 use crate::mat::Mat;
 use crate::mat_eqn_solver::MatEqnSolver;
+use crate::qr_dec::QrDec;
 use rand::random;
 use std::cmp::Ordering;
 use std::marker::PhantomData;
@@ -15,39 +17,45 @@ pub struct PowerMethod;
 
 impl EigenSolve for PowerMethod {
     fn eigen_solve(m: &Mat, _: f64, accuracy: f64) -> Result<f64, ()> {
-        const MAX_ITER: usize = 10_000;
+        power_method(m, accuracy)
+    }
+}
 
-        assert!(m.is_square());
-        let mut x: Vec<f64> = (0..m.rows()).map(|_| random::<f64>() * 2. - 1.).collect();
-        let mut u_prev = 0.;
-        let mut curr_iter = 0;
-        loop {
-            if curr_iter >= MAX_ITER {
-                return Err(());
-            }
-            let xpk_index = find_max_mag(&x);
+// power method for any linear operator, such as a sparse matrix
+pub fn power_method<A: LinearOperator>(m: &A, accuracy: f64) -> Result<f64, ()> {
+    const MAX_ITER: usize = 10_000;
+
+    assert_eq!(m.rows(), m.cols());
+    let mut x: Vec<f64> = (0..m.rows()).map(|_| random::<f64>() * 2. - 1.).collect();
+    let mut u_prev = 0.;
+    let mut curr_iter = 0;
+    loop {
+        if curr_iter >= MAX_ITER {
+            return Err(());
+        }
+        let xpk_index = find_max_mag(&x);
 
-            let y = m * &x;
-            let u = y[xpk_index];
+        let y = m.mul_vec(&x);
+        let u = y[xpk_index];
 
-            if f64::abs(u - u_prev) < accuracy {
-                return Ok(u);
-            }
+        if f64::abs(u - u_prev) < accuracy {
+            return Ok(u);
+        }
 
-            let ypk_index = find_max_mag(&y);
-            let ypk = y[ypk_index];
+        let ypk_index = find_max_mag(&y);
+        let ypk = y[ypk_index];
 
-            let mut x1: Vec<f64> = y.iter().map(|v| *v / ypk).collect();
-            swap(&mut x, &mut x1);
-            u_prev = u;
-            curr_iter += 1;
-        }
+        let mut x1: Vec<f64> = y.iter().map(|v| *v / ypk).collect();
+        swap(&mut x, &mut x1);
+        u_prev = u;
+        curr_iter += 1;
     }
 }
 
-fn find_max_mag<T: AsRef<[f64]>>(v: T) -> usize {
+// index of the first value with the largest magnitude
+pub(crate) fn find_max_mag<T: AsRef<[f64]>>(v: T) -> usize {
     let x = v.as_ref();
-    let i = (0..x.len())
+    (0..x.len())
         .max_by(|v1, v2| {
             let max = f64::max(x[*v1].abs(), x[*v2].abs());
             if max == x[*v1].abs() {
@@ -56,8 +64,7 @@ fn find_max_mag<T: AsRef<[f64]>>(v: T) -> usize {
                 Ordering::Less
             }
         })
-        .unwrap_or(0);
-    i
+        .unwrap_or(0)
 }
 
 // typical inverse power method for calculating eigenvalues
@@ -98,3 +105,56 @@ impl<S: MatEqnSolver> EigenSolve for InversePowerMethod<S> {
         }
     }
 }
+
+// unshifted QR algorithm. iterating A_k+1 = R_k Q_k, where A_k = Q_k R_k,
+// converges to an upper triangular matrix with the eigenvalues on the diagonal,
+// provided the eigenvalues are real and of distinct magnitude.
+// eigen_solve returns the eigenvalue closest to q
+pub struct QrAlgorithm<Q: QrDec> {
+    q: PhantomData<*const Q>,
+}
+
+impl<Q: QrDec> QrAlgorithm<Q> {
+    // calculate every eigenvalue of the matrix
+    pub fn eigenvalues(mat: &Mat, accuracy: f64) -> Result<Vec<f64>, ()> {
+        const MAX_ITER: usize = 10_000;
+
+        assert!(mat.is_square());
+        let mut a = mat.clone();
+        let mut curr_iter = 0;
+        loop {
+            let below_diag = (1..a.rows())
+                .flat_map(|i| (0..i).map(move |j| (i, j)))
+                .map(|(i, j)| f64::abs(a.get(i, j)))
+                .fold(0., f64::max);
+            if below_diag < accuracy {
+                return Ok((0..a.rows()).map(|i| a.get(i, i)).collect());
+            }
+            if curr_iter >= MAX_ITER {
+                return Err(());
+            }
+            let (q, r) = Q::dec(a)?;
+            a = &r * &q;
+            curr_iter += 1;
+        }
+    }
+}
+
+impl<Q: QrDec> EigenSolve for QrAlgorithm<Q> {
+    fn eigen_solve(mat: &Mat, q: f64, accuracy: f64) -> Result<f64, ()> {
+        let es = Self::eigenvalues(mat, accuracy)?;
+        closest_to(&es, q)
+    }
+}
+
+// the eigenvalue closest to q, failing if there are none
+pub(crate) fn closest_to(es: &[f64], q: f64) -> Result<f64, ()> {
+    es.iter()
+        .cloned()
+        .min_by(|e1, e2| {
+            f64::abs(e1 - q)
+                .partial_cmp(&f64::abs(e2 - q))
+                .unwrap_or(Ordering::Equal)
+        })
+        .ok_or(())
+}
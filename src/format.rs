@@ -0,0 +1,167 @@
+use crate::mat::Mat;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+// how numbers are written out
+pub enum Notation {
+    // fixed point, precision is the number of decimal places
+    Fixed,
+    // scientific, precision is the number of digits after the decimal point
+    Scientific,
+    // precision is the number of significant digits. fixed point is used
+    // unless the value is tiny or huge, then scientific. trailing zeros are
+    // dropped, so 0.5 is written as 0.5 rather than 0.500000
+    Significant,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+// options for formatting the numbers in a matrix
+pub struct NumFormat {
+    pub precision: usize,
+    pub notation: Notation,
+}
+
+impl NumFormat {
+    // fixed point with the given number of decimal places
+    pub fn fixed(precision: usize) -> Self {
+        NumFormat {
+            precision,
+            notation: Notation::Fixed,
+        }
+    }
+
+    // scientific with the given number of digits after the decimal point
+    pub fn scientific(precision: usize) -> Self {
+        NumFormat {
+            precision,
+            notation: Notation::Scientific,
+        }
+    }
+
+    // the given number of significant digits
+    pub fn significant(precision: usize) -> Self {
+        NumFormat {
+            precision,
+            notation: Notation::Significant,
+        }
+    }
+
+    // format a single number for LaTeX, writing any exponent as a power of 10
+    pub fn format_latex(&self, v: f64) -> String {
+        latex_num(&self.format(v))
+    }
+
+    // format a single number
+    pub fn format(&self, v: f64) -> String {
+        let s = match self.notation {
+            Notation::Fixed => format!("{:.*}", self.precision, v),
+            Notation::Scientific => format!("{:.*e}", self.precision, v),
+            Notation::Significant => significant(v, self.precision.max(1)),
+        };
+        // small negative values round to "-0.000", drop the sign
+        if s.starts_with('-') && s.parse::<f64>() == Ok(0.0) {
+            s[1..].to_string()
+        } else {
+            s
+        }
+    }
+}
+
+impl Default for NumFormat {
+    fn default() -> Self {
+        NumFormat::fixed(6)
+    }
+}
+
+impl Mat {
+    // format the matrix as a LaTeX bmatrix
+    pub fn to_latex(&self, fmt: NumFormat) -> String {
+        let mut res = String::from("\\begin{bmatrix}\n");
+        for (i, row) in self.iter().enumerate() {
+            let cells: Vec<String> = row.iter().map(|v| fmt.format_latex(*v)).collect();
+            res.push_str(&cells.join(" & "));
+            if i + 1 != self.rows() {
+                res.push_str(" \\\\");
+            }
+            res.push('\n');
+        }
+        res.push_str("\\end{bmatrix}");
+        res
+    }
+
+    // format the matrix as a Markdown table, with the
+    // column numbers as the header row
+    pub fn to_markdown(&self, fmt: NumFormat) -> String {
+        let cells: Vec<Vec<String>> = self
+            .iter()
+            .map(|row| row.iter().map(|v| fmt.format(*v)).collect())
+            .collect();
+        let widths: Vec<usize> = (0..self.cols())
+            .map(|c| {
+                cells
+                    .iter()
+                    .map(|row| row[c].len())
+                    .chain(std::iter::once(format!("{}", c + 1).len()))
+                    .max()
+                    .unwrap_or(3)
+                    .max(3)
+            })
+            .collect();
+
+        let mut res = String::new();
+        let header: Vec<String> = widths
+            .iter()
+            .enumerate()
+            .map(|(c, w)| format!("{:>w$}", c + 1, w = w))
+            .collect();
+        res.push_str(&format!("| {} |\n", header.join(" | ")));
+        let rule: Vec<String> = widths
+            .iter()
+            .map(|w| format!("{}:", "-".repeat(w - 1)))
+            .collect();
+        res.push_str(&format!("| {} |\n", rule.join(" | ")));
+        for row in cells.iter() {
+            let row: Vec<String> = row
+                .iter()
+                .zip(widths.iter())
+                .map(|(v, w)| format!("{:>w$}", v, w = w))
+                .collect();
+            res.push_str(&format!("| {} |\n", row.join(" | ")));
+        }
+        res
+    }
+}
+
+// format v with p significant digits, like %g in C
+fn significant(v: f64, p: usize) -> String {
+    if v == 0.0 || !v.is_finite() {
+        return format!("{}", v);
+    }
+    // round first, so 9.9999 becomes 1.000e1 and picks the right exponent
+    let s = format!("{:.*e}", p - 1, v);
+    let i = s.find('e').unwrap();
+    let exp: i32 = s[i + 1..].parse().unwrap();
+    if exp < -4 || exp >= p as i32 {
+        format!("{}e{}", trim_zeros(&s[..i]), exp)
+    } else {
+        let decimals = (p as i32 - 1 - exp) as usize;
+        trim_zeros(&format!("{:.*}", decimals, v)).to_string()
+    }
+}
+
+// drop trailing zeros after the decimal point, and the point itself
+fn trim_zeros(s: &str) -> &str {
+    if s.contains('.') {
+        s.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        s
+    }
+}
+
+// rust writes scientific notation as 1.5e-7, which LaTeX would
+// typeset literally, so rewrite it as 1.5 \times 10^{-7}
+fn latex_num(s: &str) -> String {
+    match s.find('e') {
+        Some(i) => format!("{} \\times 10^{{{}}}", &s[..i], &s[i + 1..]),
+        None => s.to_string(),
+    }
+}
@@ -1,6 +1,9 @@
-use crate::mat::Mat;
+use crate::lu_dec::{LuDec, PivotedDoolittle, PivotedLuDec};
+use crate::mat::{Mat, RowOperation};
 use crate::reduce_upper::ReduceUpper;
-use crate::upper_triangle::UpperTriangle;
+use crate::svd::SvdDec;
+use crate::triangular::{LowerTriangular, UpperTriangular};
+use crate::upper_triangle::{find_pivot, UpperTriangle};
 use std::marker::PhantomData;
 
 // trait representing the process of inverting a matrix
@@ -30,3 +33,239 @@ impl<T: UpperTriangle, R: ReduceUpper> Invert for AugmentedMat<T, R> {
         Ok(res)
     }
 }
+
+// gauss-jordan elimination with partial pivoting. each column is
+// eliminated above and below the pivot in a single sweep, performing
+// the same row operations on an identity matrix
+pub struct GaussJordan;
+
+impl Invert for GaussJordan {
+    fn invert(mut m: Mat) -> Result<Mat, ()> {
+        assert!(m.is_square());
+        let n = m.rows();
+        let mut res = Mat::new_i(n);
+        for i in 0..n {
+            let p = find_pivot(&m, i, i);
+            if m.get(p, i) == 0.0 {
+                return Err(());
+            }
+            let mut ops = vec![];
+            if p != i {
+                ops.push(RowOperation::Swap(i, p));
+            }
+            ops.push(RowOperation::Scale {
+                row: i,
+                scale: 1.0 / m.get(p, i),
+            });
+            for op in ops.iter() {
+                m.apply(op);
+                res.apply(op);
+            }
+            for j in (0..n).filter(|j| *j != i) {
+                let op = RowOperation::Cmb {
+                    src: i,
+                    scale: m.get(j, i),
+                    dest: j,
+                };
+                m.apply(&op);
+                res.apply(&op);
+            }
+        }
+        Ok(res)
+    }
+}
+
+// gauss-jordan elimination with partial pivoting, overwriting the input
+// matrix with its inverse instead of allocating a separate identity matrix.
+// every eliminated column of A is replaced by the matching column of A^-1,
+// and the row swaps are undone as column swaps at the end
+pub struct GaussJordanInPlace;
+
+impl Invert for GaussJordanInPlace {
+    fn invert(mut m: Mat) -> Result<Mat, ()> {
+        assert!(m.is_square());
+        let n = m.rows();
+        let mut swaps = vec![];
+        for k in 0..n {
+            let p = find_pivot(&m, k, k);
+            let pivot = m.get(p, k);
+            if pivot == 0.0 {
+                return Err(());
+            }
+            if p != k {
+                m.apply(&RowOperation::Swap(k, p));
+                swaps.push((k, p));
+            }
+            m.set(k, k, 1.0);
+            for c in 0..n {
+                m.set(k, c, m.get(k, c) / pivot);
+            }
+            for r in (0..n).filter(|r| *r != k) {
+                let f = m.get(r, k);
+                m.set(r, k, 0.0);
+                for c in 0..n {
+                    m.set(r, c, m.get(r, c) - f * m.get(k, c));
+                }
+            }
+        }
+        for (c1, c2) in swaps.into_iter().rev() {
+            for r in 0..n {
+                let tmp = m.get(r, c1);
+                m.set(r, c1, m.get(r, c2));
+                m.set(r, c2, tmp);
+            }
+        }
+        Ok(m)
+    }
+}
+
+// factor A = LU with D, then solve LUx = e_j with forward and back
+// substitution for every column e_j of the identity matrix. fails if A
+// needs row swaps, which PivotedLuInvert supports
+pub struct LuInvert<D: LuDec> {
+    d: PhantomData<*const D>,
+}
+
+impl<D: LuDec> Invert for LuInvert<D> {
+    fn invert(m: Mat) -> Result<Mat, ()> {
+        assert!(m.is_square());
+        let n = m.rows();
+        let (l, u) = D::dec(m)?;
+        let mut res = Mat::new(n, n);
+        for j in 0..n {
+            let mut e = vec![0.0; n];
+            e[j] = 1.0;
+            let x = u.solve(&l.solve(&e)?)?;
+            for (i, v) in x.iter().enumerate() {
+                res.set(i, j, *v);
+            }
+        }
+        Ok(res)
+    }
+}
+
+// factor PA = LU, allowing T to swap rows, then solve LUx = Pe_j
+// for every column e_j of the identity matrix
+pub struct PivotedLuInvert<T: UpperTriangle> {
+    t: PhantomData<*const T>,
+}
+
+impl<T: UpperTriangle> Invert for PivotedLuInvert<T> {
+    fn invert(m: Mat) -> Result<Mat, ()> {
+        assert!(m.is_square());
+        let n = m.rows();
+        let (perm, l, u) = PivotedDoolittle::<T>::dec(m)?;
+        let mut res = Mat::new(n, n);
+        for j in 0..n {
+            let e: Vec<f64> = perm
+                .iter()
+                .map(|r| if *r == j { 1.0 } else { 0.0 })
+                .collect();
+            let x = u.solve(&l.solve(&e)?)?;
+            for (i, v) in x.iter().enumerate() {
+                res.set(i, j, *v);
+            }
+        }
+        Ok(res)
+    }
+}
+
+// invert a lower triangular matrix, only touching the lower triangle.
+// fails if there is a zero on the diagonal
+pub fn invert_lower(l: &Mat) -> Result<Mat, ()> {
+    LowerTriangular::from(l).invert().map(|inv| Mat::from(&inv))
+}
+
+// invert an upper triangular matrix, only touching the upper triangle.
+// fails if there is a zero on the diagonal
+pub fn invert_upper(u: &Mat) -> Result<Mat, ()> {
+    UpperTriangular::from(u).invert().map(|inv| Mat::from(&inv))
+}
+
+// moore-penrose pseudoinverse A+ = V S+ U^T, computed from the SVD with S.
+// singular values below max(m, n) * eps * sigma_max are treated as zero,
+// so rectangular and rank deficient matrices are supported
+pub struct PseudoInverse<S: SvdDec> {
+    s: PhantomData<*const S>,
+}
+
+impl<S: SvdDec> Invert for PseudoInverse<S> {
+    fn invert(m: Mat) -> Result<Mat, ()> {
+        let rows = m.rows();
+        let cols = m.cols();
+        let (u, s, vt) = S::dec(m)?;
+        let tol = (rows.max(cols) as f64) * f64::EPSILON * s[0];
+
+        let mut res = Mat::new(cols, rows);
+        for (k, sigma) in s.iter().enumerate() {
+            if *sigma <= tol {
+                continue;
+            }
+            for r in 0..cols {
+                for c in 0..rows {
+                    res.set(r, c, res.get(r, c) + vt.get(k, r) * u.get(c, k) / sigma);
+                }
+            }
+        }
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lu_dec::Doolittle;
+    use crate::svd::OneSidedJacobi;
+    use crate::upper_triangle::Gaussian;
+
+    fn mat(rows: usize, cols: usize, values: &[f64]) -> Mat {
+        let mut m = Mat::new(rows, cols);
+        for (i, v) in values.iter().enumerate() {
+            m.set(i / cols, i % cols, *v);
+        }
+        m
+    }
+
+    fn assert_close(a: &Mat, b: &Mat) {
+        assert_eq!((a.rows(), a.cols()), (b.rows(), b.cols()));
+        for (ra, rb) in a.iter().zip(b.iter()) {
+            for (x, y) in ra.iter().zip(rb.iter()) {
+                assert!((x - y).abs() < 1e-10, "\n{}\n!=\n{}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn pseudo_inverse_satisfies_penrose_conditions() {
+        for m in [
+            mat(3, 3, &[1., 2., 3., 2., 4., 6., 1., 0., 1.]),
+            mat(3, 3, &[2., 1., 3., 4., 2., 6., 0., 1., 1.]),
+            mat(3, 2, &[1., 2., 3., 4., 5., 6.]),
+            mat(2, 3, &[1., 2., 3., 4., 5., 6.]),
+            mat(3, 2, &[1., 3., 2., 6., 3., 9.]),
+            mat(2, 3, &[1., 2., 3., 2., 4., 6.]),
+        ]
+        .iter()
+        {
+            let p = PseudoInverse::<OneSidedJacobi>::invert(m.clone()).unwrap();
+            assert_eq!((p.rows(), p.cols()), (m.cols(), m.rows()));
+            assert_close(&(&(m * &p) * m), m);
+            assert_close(&(&(&p * m) * &p), &p);
+        }
+    }
+
+    #[test]
+    fn pseudo_inverse_of_invertible_is_inverse() {
+        let m = mat(2, 2, &[4., 7., 2., 6.]);
+        let p = PseudoInverse::<OneSidedJacobi>::invert(m.clone()).unwrap();
+        assert_close(&(&m * &p), &Mat::new_i(2));
+    }
+
+    #[test]
+    fn lu_invert_needs_pivoting_for_a_zero_pivot() {
+        let m = mat(3, 3, &[0., 1., 2., 1., 0., 3., 4., 5., 6.]);
+        assert!(LuInvert::<Doolittle<Gaussian>>::invert(m.clone()).is_err());
+        let inv = PivotedLuInvert::<Gaussian>::invert(m.clone()).unwrap();
+        assert_close(&(&m * &inv), &Mat::new_i(3));
+    }
+}
@@ -0,0 +1,446 @@
+use crate::mat::Mat;
+use crate::npy::{self, Order};
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+#[derive(Debug)]
+// error reading a matrix
+pub enum ReadError {
+    // the underlying reader failed
+    Io(io::Error),
+    // the input contained no values
+    Empty,
+    // the input is malformed, lines are numbered from 1
+    Parse { line: usize, msg: String },
+    // a binary input is malformed
+    Format(String),
+}
+
+impl ReadError {
+    fn parse<S: Into<String>>(line: usize, msg: S) -> Self {
+        ReadError::Parse {
+            line,
+            msg: msg.into(),
+        }
+    }
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReadError::Io(e) => write!(f, "{}", e),
+            ReadError::Empty => write!(f, "no matrix values found"),
+            ReadError::Parse { line, msg } => write!(f, "line {}: {}", line, msg),
+            ReadError::Format(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl Error for ReadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ReadError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ReadError {
+    fn from(e: io::Error) -> Self {
+        ReadError::Io(e)
+    }
+}
+
+// largest matrix the readers will allocate, 2^27 values (1 GiB of f64),
+// so a corrupt or hostile size fails cleanly instead of exhausting memory
+pub(crate) const MAX_VALUES: usize = 1 << 27;
+
+// number of values in a rows x cols matrix, if it is at most MAX_VALUES
+pub(crate) fn checked_len(rows: usize, cols: usize) -> Option<usize> {
+    rows.checked_mul(cols).filter(|len| *len <= MAX_VALUES)
+}
+
+// parse a single value, naming it in the error
+fn parse_num(line: usize, s: &str) -> Result<f64, ReadError> {
+    s.parse()
+        .map_err(|_| ReadError::parse(line, format!("invalid number {:?}", s)))
+}
+
+// parse a 1-based index or a size
+fn parse_usize(line: usize, s: &str) -> Result<usize, ReadError> {
+    s.parse()
+        .map_err(|_| ReadError::parse(line, format!("invalid integer {:?}", s)))
+}
+
+// the non-blank lines, numbered from first, skipping
+// any line that starts with the comment character
+fn content_lines<I>(
+    lines: I,
+    first: usize,
+    comment: char,
+) -> Result<Vec<(usize, String)>, ReadError>
+where
+    I: Iterator<Item = io::Result<String>>,
+{
+    let mut res = Vec::new();
+    for (i, line) in lines.enumerate() {
+        let line = line?;
+        let trimmed = line.trim();
+        if !trimmed.is_empty() && !trimmed.starts_with(comment) {
+            res.push((i + first, trimmed.to_string()));
+        }
+    }
+    Ok(res)
+}
+
+// construct a matrix from its rows, which must all be the same length
+fn from_rows(rows: Vec<(usize, Vec<f64>)>) -> Result<Mat, ReadError> {
+    let cols = match rows.first() {
+        Some((_, row)) => row.len(),
+        None => return Err(ReadError::Empty),
+    };
+    let mut res = Mat::new(rows.len(), cols);
+    for (r, (line, row)) in rows.iter().enumerate() {
+        if row.len() != cols {
+            return Err(ReadError::parse(
+                *line,
+                format!("expected {} values, found {}", cols, row.len()),
+            ));
+        }
+        for (c, v) in row.iter().enumerate() {
+            res.set(r, c, *v);
+        }
+    }
+    Ok(res)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Symmetry {
+    General,
+    Symmetric,
+    SkewSymmetric,
+}
+
+// read a matrix in matrix market format, either dense (array)
+// or sparse (coordinate). real, integer and pattern fields are
+// supported, with general, symmetric or skew-symmetric storage
+pub fn read_matrix_market<R: BufRead>(r: R) -> Result<Mat, ReadError> {
+    let mut lines = r.lines();
+    let header = match lines.next() {
+        Some(line) => line?,
+        None => return Err(ReadError::Empty),
+    };
+    let tokens: Vec<String> = header
+        .split_whitespace()
+        .map(|t| t.to_lowercase())
+        .collect();
+    if tokens.len() != 5 || tokens[0] != "%%matrixmarket" || tokens[1] != "matrix" {
+        return Err(ReadError::parse(
+            1,
+            "expected header \"%%MatrixMarket matrix <format> <field> <symmetry>\"",
+        ));
+    }
+    let coordinate = match tokens[2].as_str() {
+        "coordinate" => true,
+        "array" => false,
+        f => return Err(ReadError::parse(1, format!("unknown format {:?}", f))),
+    };
+    let pattern = match tokens[3].as_str() {
+        "real" | "double" | "integer" => false,
+        "pattern" if coordinate => true,
+        f => return Err(ReadError::parse(1, format!("unsupported field {:?}", f))),
+    };
+    let symmetry = match tokens[4].as_str() {
+        "general" => Symmetry::General,
+        "symmetric" => Symmetry::Symmetric,
+        "skew-symmetric" => Symmetry::SkewSymmetric,
+        s => return Err(ReadError::parse(1, format!("unsupported symmetry {:?}", s))),
+    };
+
+    let mut body = content_lines(lines, 2, '%')?.into_iter();
+
+    let (size_line, size) = body.next().ok_or(ReadError::Empty)?;
+    let size: Vec<&str> = size.split_whitespace().collect();
+    let expected = if coordinate { 3 } else { 2 };
+    if size.len() != expected {
+        return Err(ReadError::parse(
+            size_line,
+            format!("expected {} sizes, found {}", expected, size.len()),
+        ));
+    }
+    let rows = parse_usize(size_line, size[0])?;
+    let cols = parse_usize(size_line, size[1])?;
+    if rows == 0 || cols == 0 {
+        return Err(ReadError::Empty);
+    }
+    if symmetry != Symmetry::General && rows != cols {
+        return Err(ReadError::parse(
+            size_line,
+            "symmetric matrices must be square",
+        ));
+    }
+    // check the sizes before allocating anything
+    let len = checked_len(rows, cols).ok_or_else(|| {
+        ReadError::parse(
+            size_line,
+            format!("a {} x {} matrix is too large", rows, cols),
+        )
+    })?;
+    let nnz = if coordinate {
+        let nnz = parse_usize(size_line, size[2])?;
+        if nnz > len {
+            return Err(ReadError::parse(
+                size_line,
+                format!("{} entries do not fit in a {} x {} matrix", nnz, rows, cols),
+            ));
+        }
+        nnz
+    } else {
+        // arrays list every value of the stored triangle instead
+        0
+    };
+    let mut res = Mat::new(rows, cols);
+
+    // store a value, mirroring it for symmetric storage
+    let mut put = |line: usize, r: usize, c: usize, v: f64| -> Result<(), ReadError> {
+        if symmetry != Symmetry::General && c > r {
+            return Err(ReadError::parse(
+                line,
+                "symmetric matrices only store the lower triangle",
+            ));
+        }
+        if symmetry == Symmetry::SkewSymmetric && r == c {
+            return Err(ReadError::parse(
+                line,
+                "skew-symmetric matrices have no diagonal entries",
+            ));
+        }
+        res.set(r, c, v);
+        match symmetry {
+            Symmetry::General => {}
+            Symmetry::Symmetric => res.set(c, r, v),
+            Symmetry::SkewSymmetric => res.set(c, r, -v),
+        }
+        Ok(())
+    };
+
+    let mut count = 0;
+    if coordinate {
+        for (line, entry) in body {
+            let entry: Vec<&str> = entry.split_whitespace().collect();
+            let expected = if pattern { 2 } else { 3 };
+            if entry.len() != expected {
+                return Err(ReadError::parse(
+                    line,
+                    format!("expected {} fields, found {}", expected, entry.len()),
+                ));
+            }
+            let r = parse_usize(line, entry[0])?;
+            let c = parse_usize(line, entry[1])?;
+            if r == 0 || r > rows || c == 0 || c > cols {
+                return Err(ReadError::parse(
+                    line,
+                    format!(
+                        "entry ({}, {}) is outside a {} x {} matrix",
+                        r, c, rows, cols
+                    ),
+                ));
+            }
+            let v = if pattern {
+                1.0
+            } else {
+                parse_num(line, entry[2])?
+            };
+            put(line, r - 1, c - 1, v)?;
+            count += 1;
+        }
+        if count != nnz {
+            return Err(ReadError::parse(
+                size_line,
+                format!("expected {} entries, found {}", nnz, count),
+            ));
+        }
+    } else {
+        // values are listed column by column, only the stored
+        // triangle is listed for symmetric matrices
+        let mut positions = (0..cols).flat_map(|c| {
+            let first = match symmetry {
+                Symmetry::General => 0,
+                Symmetry::Symmetric => c,
+                Symmetry::SkewSymmetric => c + 1,
+            };
+            (first..rows).map(move |r| (r, c))
+        });
+        let expected = positions.clone().count();
+        for (line, entry) in body {
+            for v in entry.split_whitespace() {
+                let v = parse_num(line, v)?;
+                count += 1;
+                match positions.next() {
+                    Some((r, c)) => put(line, r, c, v)?,
+                    None => {
+                        return Err(ReadError::parse(
+                            line,
+                            format!("expected {} values, found more", expected),
+                        ))
+                    }
+                }
+            }
+        }
+        if count != expected {
+            return Err(ReadError::parse(
+                size_line,
+                format!("expected {} values, found {}", expected, count),
+            ));
+        }
+    }
+    Ok(res)
+}
+
+// write a matrix in dense matrix market format
+pub fn write_matrix_market<W: Write>(m: &Mat, mut w: W) -> io::Result<()> {
+    writeln!(w, "%%MatrixMarket matrix array real general")?;
+    writeln!(w, "{} {}", m.rows(), m.cols())?;
+    for c in 0..m.cols() {
+        for r in 0..m.rows() {
+            writeln!(w, "{}", m.get(r, c))?;
+        }
+    }
+    Ok(())
+}
+
+// write the non-zero values of a matrix in sparse matrix market format
+pub fn write_matrix_market_coordinate<W: Write>(m: &Mat, mut w: W) -> io::Result<()> {
+    let entries: Vec<(usize, usize, f64)> = m
+        .iter()
+        .enumerate()
+        .flat_map(|(r, row)| {
+            row.iter()
+                .enumerate()
+                .filter(|(_, v)| **v != 0.0)
+                .map(move |(c, v)| (r, c, *v))
+        })
+        .collect();
+    writeln!(w, "%%MatrixMarket matrix coordinate real general")?;
+    writeln!(w, "{} {} {}", m.rows(), m.cols(), entries.len())?;
+    for (r, c, v) in entries {
+        writeln!(w, "{} {} {}", r + 1, c + 1, v)?;
+    }
+    Ok(())
+}
+
+// read a matrix with one row per line and values separated by delim,
+// e.g. ',' for CSV or '\t' for TSV. lines starting with '#' are skipped
+pub fn read_delimited<R: BufRead>(r: R, delim: char) -> Result<Mat, ReadError> {
+    let rows = content_lines(r.lines(), 1, '#')?
+        .into_iter()
+        .map(|(line, s)| {
+            let row = s
+                .split(delim)
+                .map(|v| parse_num(line, v.trim()))
+                .collect::<Result<Vec<f64>, _>>()?;
+            Ok((line, row))
+        })
+        .collect::<Result<Vec<_>, ReadError>>()?;
+    from_rows(rows)
+}
+
+// write a matrix with one row per line and values separated by delim
+pub fn write_delimited<W: Write>(m: &Mat, mut w: W, delim: char) -> io::Result<()> {
+    for row in m.iter() {
+        let row: Vec<String> = row.iter().map(|v| v.to_string()).collect();
+        writeln!(w, "{}", row.join(&delim.to_string()))?;
+    }
+    Ok(())
+}
+
+// read a matrix with one row per line and values separated by
+// any amount of whitespace. lines starting with '#' are skipped
+pub fn read_whitespace<R: BufRead>(r: R) -> Result<Mat, ReadError> {
+    let rows = content_lines(r.lines(), 1, '#')?
+        .into_iter()
+        .map(|(line, s)| {
+            let row = s
+                .split_whitespace()
+                .map(|v| parse_num(line, v))
+                .collect::<Result<Vec<f64>, _>>()?;
+            Ok((line, row))
+        })
+        .collect::<Result<Vec<_>, ReadError>>()?;
+    from_rows(rows)
+}
+
+// write a matrix with one row per line and values separated by a space
+pub fn write_whitespace<W: Write>(m: &Mat, w: W) -> io::Result<()> {
+    write_delimited(m, w, ' ')
+}
+
+// read a matrix from a file, choosing the format by its extension:
+// .mtx is matrix market, .npy is numpy, .csv and .tsv are delimited,
+// and anything else is read as whitespace separated
+pub fn read_file<P: AsRef<Path>>(path: P) -> Result<Mat, ReadError> {
+    let path = path.as_ref();
+    let r = BufReader::new(File::open(path)?);
+    match extension(path).as_str() {
+        "mtx" => read_matrix_market(r),
+        "npy" => npy::read_npy(r),
+        "csv" => read_delimited(r, ','),
+        "tsv" => read_delimited(r, '\t'),
+        _ => read_whitespace(r),
+    }
+}
+
+// write a matrix to a file, choosing the format by its extension
+// as in read_file. matrix market files are written dense
+pub fn write_file<P: AsRef<Path>>(m: &Mat, path: P) -> io::Result<()> {
+    let path = path.as_ref();
+    let mut w = BufWriter::new(File::create(path)?);
+    match extension(path).as_str() {
+        "mtx" => write_matrix_market(m, &mut w)?,
+        "npy" => npy::write_npy(m, &mut w, Order::C)?,
+        "csv" => write_delimited(m, &mut w, ',')?,
+        "tsv" => write_delimited(m, &mut w, '\t')?,
+        _ => write_whitespace(m, &mut w)?,
+    }
+    w.flush()
+}
+
+fn extension(path: &Path) -> String {
+    path.extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(s: &str) -> Result<Mat, ReadError> {
+        read_matrix_market(s.as_bytes())
+    }
+
+    #[test]
+    fn huge_sizes_are_rejected_before_allocating() {
+        let overflow = "%%MatrixMarket matrix array real general\n\
+                        18446744073709551615 2\n";
+        assert!(matches!(
+            read(overflow),
+            Err(ReadError::Parse { line: 2, .. })
+        ));
+        let too_large = "%%MatrixMarket matrix coordinate real general\n\
+                         1000000 1000000 0\n";
+        assert!(matches!(
+            read(too_large),
+            Err(ReadError::Parse { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn nnz_must_fit_in_the_matrix() {
+        let s = "%%MatrixMarket matrix coordinate real general\n2 2 5\n";
+        assert!(matches!(read(s), Err(ReadError::Parse { line: 2, .. })));
+    }
+}
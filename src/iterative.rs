@@ -0,0 +1,165 @@
+use crate::linear_operator::LinearOperator;
+use crate::mat::Mat;
+use crate::mat_eqn_solver::MatEqnSolver;
+use crate::norm::{LInf, VecNorm};
+use std::marker::PhantomData;
+
+// trait representing when an iterative solver should stop, failing if
+// it hasn't after MAX_ITER iterations. TOL is relative, but what it
+// bounds depends on the solver:
+// - the stationary solvers here stop once
+//   ||x_k+1 - x_k||_inf <= TOL * ||x_k+1||_inf
+// - the krylov solvers (ConjugateGradient, Gmres) stop once the
+//   residual ||b - Ax_k||_2 <= TOL * ||b||_2
+pub trait Convergence {
+    const TOL: f64;
+    const MAX_ITER: usize;
+}
+
+// tolerance of 1e-10, up to 10,000 iterations
+pub struct DefaultConvergence;
+
+impl Convergence for DefaultConvergence {
+    const TOL: f64 = 1e-10;
+    const MAX_ITER: usize = 10_000;
+}
+
+// trait representing the relaxation parameter used by SOR
+pub trait Relaxation {
+    const OMEGA: f64;
+}
+
+// omega = 1.5, a reasonable over-relaxation for many problems
+pub struct DefaultRelaxation;
+
+impl Relaxation for DefaultRelaxation {
+    const OMEGA: f64 = 1.5;
+}
+
+impl Mat {
+    // is every diagonal entry strictly larger in magnitude
+    // than the sum of the other entries in its row?
+    // jacobi and gauss-seidel are guaranteed to converge if so
+    pub fn is_diag_dominant(&self) -> bool {
+        assert!(self.is_square());
+        (0..self.rows()).all(|r| {
+            let off_diag: f64 = self
+                .iter_row(r)
+                .enumerate()
+                .filter(|(c, _)| *c != r)
+                .map(|(_, v)| f64::abs(*v))
+                .sum();
+            f64::abs(self.get(r, r)) > off_diag
+        })
+    }
+}
+
+// have we converged, given the size of the last update?
+fn converged(delta: f64, x: &[f64], tol: f64) -> Result<bool, ()> {
+    if !delta.is_finite() {
+        // diverged
+        return Err(());
+    }
+    Ok(delta <= tol * LInf::vec_norm(x))
+}
+
+// jacobi iteration, x_k+1 = D^-1 (b - (L + U) x_k)
+pub fn jacobi<A: LinearOperator>(
+    m: &A,
+    b: &[f64],
+    tol: f64,
+    max_iter: usize,
+) -> Result<Vec<f64>, ()> {
+    assert_eq!(m.rows(), m.cols());
+    assert_eq!(m.rows(), b.len());
+    let n = m.rows();
+    if (0..n).any(|i| m.diag(i) == 0.0) {
+        return Err(());
+    }
+    let mut x = vec![0.0; n];
+    let mut x1 = vec![0.0; n];
+    for _ in 0..max_iter {
+        let mut delta: f64 = 0.0;
+        for i in 0..n {
+            let d = m.diag(i);
+            let s = m.row_dot(i, &x) - d * x[i];
+            x1[i] = (b[i] - s) / d;
+            delta = delta.max(f64::abs(x1[i] - x[i]));
+        }
+        std::mem::swap(&mut x, &mut x1);
+        if converged(delta, &x, tol)? {
+            return Ok(x);
+        }
+    }
+    Err(())
+}
+
+// successive over-relaxation. a gauss-seidel sweep, using each new value
+// as soon as it is available, with the update scaled by omega.
+// omega = 1 is plain gauss-seidel
+pub fn sor<A: LinearOperator>(
+    m: &A,
+    b: &[f64],
+    omega: f64,
+    tol: f64,
+    max_iter: usize,
+) -> Result<Vec<f64>, ()> {
+    assert_eq!(m.rows(), m.cols());
+    assert_eq!(m.rows(), b.len());
+    assert!(omega > 0.0 && omega < 2.0);
+    let n = m.rows();
+    if (0..n).any(|i| m.diag(i) == 0.0) {
+        return Err(());
+    }
+    let mut x = vec![0.0; n];
+    for _ in 0..max_iter {
+        let mut delta: f64 = 0.0;
+        for i in 0..n {
+            let d = m.diag(i);
+            let s = m.row_dot(i, &x) - d * x[i];
+            let gs = (b[i] - s) / d;
+            let xi = x[i] + omega * (gs - x[i]);
+            delta = delta.max(f64::abs(xi - x[i]));
+            x[i] = xi;
+        }
+        if converged(delta, &x, tol)? {
+            return Ok(x);
+        }
+    }
+    Err(())
+}
+
+// jacobi iteration, stopping according to C
+pub struct Jacobi<C: Convergence> {
+    c: PhantomData<*const C>,
+}
+
+impl<C: Convergence> MatEqnSolver for Jacobi<C> {
+    fn solve(m: Mat, b: Vec<f64>) -> Result<Vec<f64>, ()> {
+        jacobi(&m, &b, C::TOL, C::MAX_ITER)
+    }
+}
+
+// gauss-seidel iteration, stopping according to C
+pub struct GaussSeidel<C: Convergence> {
+    c: PhantomData<*const C>,
+}
+
+impl<C: Convergence> MatEqnSolver for GaussSeidel<C> {
+    fn solve(m: Mat, b: Vec<f64>) -> Result<Vec<f64>, ()> {
+        sor(&m, &b, 1.0, C::TOL, C::MAX_ITER)
+    }
+}
+
+// successive over-relaxation with relaxation parameter W,
+// stopping according to C
+pub struct Sor<W: Relaxation, C: Convergence> {
+    w: PhantomData<*const W>,
+    c: PhantomData<*const C>,
+}
+
+impl<W: Relaxation, C: Convergence> MatEqnSolver for Sor<W, C> {
+    fn solve(m: Mat, b: Vec<f64>) -> Result<Vec<f64>, ()> {
+        sor(&m, &b, W::OMEGA, C::TOL, C::MAX_ITER)
+    }
+}
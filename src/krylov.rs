@@ -0,0 +1,312 @@
+use crate::iterative::Convergence;
+use crate::linear_operator::LinearOperator;
+use crate::mat::Mat;
+use crate::mat_eqn_solver::MatEqnSolver;
+use crate::norm::{VecNorm, L2};
+use crate::sparse::Csr;
+use crate::vector::Vector;
+use std::marker::PhantomData;
+
+// trait representing a preconditioner M ~ A for operators of type A,
+// built once from A and then applied as z = M^-1 r
+pub trait Preconditioner<A>: Sized {
+    fn new(m: &A) -> Result<Self, ()>;
+    fn apply(&self, r: &[f64]) -> Vector;
+}
+
+// no preconditioning, M = I
+pub struct NoPrecond;
+
+impl<A> Preconditioner<A> for NoPrecond {
+    fn new(_: &A) -> Result<Self, ()> {
+        Ok(NoPrecond)
+    }
+
+    fn apply(&self, r: &[f64]) -> Vector {
+        Vector::from(r)
+    }
+}
+
+// jacobi (diagonal) preconditioning, M = diag(A)
+pub struct JacobiPrecond {
+    inv_diag: Vector,
+}
+
+impl<A: LinearOperator> Preconditioner<A> for JacobiPrecond {
+    fn new(m: &A) -> Result<Self, ()> {
+        assert_eq!(m.rows(), m.cols());
+        if (0..m.rows()).any(|i| m.diag(i) == 0.0) {
+            return Err(());
+        }
+        Ok(JacobiPrecond {
+            inv_diag: (0..m.rows()).map(|i| 1.0 / m.diag(i)).collect(),
+        })
+    }
+
+    fn apply(&self, r: &[f64]) -> Vector {
+        r.iter()
+            .zip(self.inv_diag.iter())
+            .map(|(v, d)| *v * *d)
+            .collect()
+    }
+}
+
+// incomplete LU factorization with no fill-in, M = LU where L and U
+// are only allowed to be non-zero where A is non-zero. the factorization
+// works row by row on the sparse pattern, so it costs about
+// O(nnz * (nnz per row)) rather than O(n^3).
+// L (unit diagonal) and U are stored together in one sparse matrix
+pub struct Ilu0 {
+    lu: Csr,
+}
+
+impl Ilu0 {
+    // factor a square sparse matrix, failing on a zero pivot
+    fn factor(m: &Csr) -> Result<Self, ()> {
+        assert_eq!(m.rows(), m.cols());
+        let n = m.rows();
+        // each row as sorted (column, value) pairs, factored in place
+        let mut rows: Vec<Vec<(usize, f64)>> = (0..n).map(|r| m.iter_row(r).collect()).collect();
+        let pivot = |row: &[(usize, f64)], k: usize| match row.binary_search_by_key(&k, |e| e.0) {
+            Ok(i) if row[i].1 != 0.0 => Ok(row[i].1),
+            _ => Err(()),
+        };
+        for i in 1..n {
+            let (done, rest) = rows.split_at_mut(i);
+            let row_i = &mut rest[0];
+            for p in 0..row_i.len() {
+                let k = row_i[p].0;
+                if k >= i {
+                    break;
+                }
+                let row_k = &done[k];
+                let l_ik = row_i[p].1 / pivot(row_k, k)?;
+                row_i[p].1 = l_ik;
+                // only update entries already in row i, no fill-in
+                for (j, u_kj) in row_k.iter().filter(|(j, _)| *j > k) {
+                    if let Ok(q) = row_i.binary_search_by_key(j, |e| e.0) {
+                        row_i[q].1 -= l_ik * u_kj;
+                    }
+                }
+            }
+        }
+        for (i, row) in rows.iter().enumerate() {
+            pivot(row, i)?;
+        }
+        let triplets: Vec<_> = rows
+            .iter()
+            .enumerate()
+            .flat_map(|(r, row)| row.iter().map(move |(c, v)| (r, *c, *v)))
+            .collect();
+        Ok(Ilu0 {
+            lu: Csr::from_triplets(n, n, &triplets),
+        })
+    }
+
+    // solve LUx = r by forward then back substitution
+    pub fn solve(&self, r: &[f64]) -> Vector {
+        let n = self.lu.rows();
+        let mut y = Vector::new(n);
+        for i in 0..n {
+            let s: f64 = self
+                .lu
+                .iter_row(i)
+                .filter(|(j, _)| *j < i)
+                .map(|(j, v)| v * y[j])
+                .sum();
+            y[i] = r[i] - s;
+        }
+        let mut x = Vector::new(n);
+        for i in (0..n).rev() {
+            let s: f64 = self
+                .lu
+                .iter_row(i)
+                .filter(|(j, _)| *j > i)
+                .map(|(j, v)| v * x[j])
+                .sum();
+            x[i] = (y[i] - s) / self.lu.get(i, i);
+        }
+        x
+    }
+}
+
+impl Preconditioner<Csr> for Ilu0 {
+    fn new(m: &Csr) -> Result<Self, ()> {
+        Ilu0::factor(m)
+    }
+
+    fn apply(&self, r: &[f64]) -> Vector {
+        self.solve(r)
+    }
+}
+
+// dense matrices are factored on their non-zero pattern
+impl Preconditioner<Mat> for Ilu0 {
+    fn new(m: &Mat) -> Result<Self, ()> {
+        assert!(m.is_square());
+        Ilu0::factor(&Csr::from(m))
+    }
+
+    fn apply(&self, r: &[f64]) -> Vector {
+        self.solve(r)
+    }
+}
+
+// preconditioned conjugate gradient, for symmetric positive definite A.
+// stops once ||b - Ax||_2 <= tol * ||b||_2
+pub fn conjugate_gradient<A: LinearOperator, P: Preconditioner<A>>(
+    m: &A,
+    b: &[f64],
+    tol: f64,
+    max_iter: usize,
+) -> Result<Vec<f64>, ()> {
+    assert_eq!(m.rows(), m.cols());
+    assert_eq!(m.rows(), b.len());
+    let p_inv = P::new(m)?;
+    let b_norm = L2::vec_norm(b);
+    let mut x = Vector::new(b.len());
+    let mut r = Vector::from(b);
+    if r.norm::<L2>() <= tol * b_norm {
+        return Ok(x.into_vec());
+    }
+    let mut z = p_inv.apply(&r);
+    let mut p = z.clone();
+    let mut rz = r.dot(&z);
+    for _ in 0..max_iter {
+        let ap = m.mul_vec(&p);
+        let pap = p.dot(&ap);
+        if pap <= 0.0 || !pap.is_finite() {
+            // A is not positive definite
+            return Err(());
+        }
+        let alpha = rz / pap;
+        x.axpy(alpha, &p);
+        r.axpy(-alpha, &ap);
+        if r.norm::<L2>() <= tol * b_norm {
+            return Ok(x.into_vec());
+        }
+        z = p_inv.apply(&r);
+        let rz_new = r.dot(&z);
+        let beta = rz_new / rz;
+        rz = rz_new;
+        p *= beta;
+        p += &z;
+    }
+    Err(())
+}
+
+// right preconditioned GMRES, restarted every `restart` iterations.
+// builds an orthonormal basis of the krylov subspace with arnoldi and
+// picks the x in it minimizing ||b - Ax||_2, using givens rotations to
+// keep the hessenberg least squares problem triangular.
+// stops once ||b - Ax||_2 <= tol * ||b||_2
+pub fn gmres<A: LinearOperator, P: Preconditioner<A>>(
+    m: &A,
+    b: &[f64],
+    restart: usize,
+    tol: f64,
+    max_iter: usize,
+) -> Result<Vec<f64>, ()> {
+    assert_eq!(m.rows(), m.cols());
+    assert_eq!(m.rows(), b.len());
+    assert!(restart > 0);
+    let p_inv = P::new(m)?;
+    let n = b.len();
+    let b_norm = L2::vec_norm(b);
+    let mut x = Vector::new(n);
+    let mut curr_iter = 0;
+    loop {
+        let mut r = Vector::from(b);
+        r -= &m.mul_vec(&x);
+        let beta = r.norm::<L2>();
+        if beta <= tol * b_norm {
+            return Ok(x.into_vec());
+        }
+        if curr_iter >= max_iter || !beta.is_finite() {
+            return Err(());
+        }
+
+        let mut v = vec![(1.0 / beta) * &r];
+        let mut h = Mat::new(restart + 1, restart);
+        let mut cs = vec![0.0; restart];
+        let mut sn = vec![0.0; restart];
+        let mut g = vec![0.0; restart + 1];
+        g[0] = beta;
+        let mut k = 0;
+        while k < restart && curr_iter < max_iter {
+            // arnoldi step with modified gram-schmidt
+            let mut w = m.mul_vec(&p_inv.apply(&v[k]));
+            for (i, vi) in v.iter().enumerate() {
+                let hik = w.dot(vi);
+                h.set(i, k, hik);
+                w.axpy(-hik, vi);
+            }
+            let w_norm = w.norm::<L2>();
+            h.set(k + 1, k, w_norm);
+
+            // apply the previous rotations to the new column
+            for i in 0..k {
+                let t = cs[i] * h.get(i, k) + sn[i] * h.get(i + 1, k);
+                h.set(i + 1, k, -sn[i] * h.get(i, k) + cs[i] * h.get(i + 1, k));
+                h.set(i, k, t);
+            }
+            // then zero out the subdiagonal entry
+            let d = h.get(k, k).hypot(h.get(k + 1, k));
+            if d == 0.0 {
+                return Err(());
+            }
+            cs[k] = h.get(k, k) / d;
+            sn[k] = h.get(k + 1, k) / d;
+            h.set(k, k, d);
+            h.set(k + 1, k, 0.0);
+            g[k + 1] = -sn[k] * g[k];
+            g[k] *= cs[k];
+
+            k += 1;
+            curr_iter += 1;
+            if f64::abs(g[k]) <= tol * b_norm || w_norm == 0.0 {
+                break;
+            }
+            v.push((1.0 / w_norm) * &w);
+        }
+
+        // solve the k x k triangular system Hy = g, then x = x + M^-1 V y
+        let mut y = vec![0.0; k];
+        for i in (0..k).rev() {
+            y[i] = (g[i] - ((i + 1)..k).map(|j| h.get(i, j) * y[j]).sum::<f64>()) / h.get(i, i);
+        }
+        let mut update = Vector::new(n);
+        for (yi, vi) in y.iter().zip(v.iter()) {
+            update.axpy(*yi, vi);
+        }
+        x += &p_inv.apply(&update);
+    }
+}
+
+// preconditioned conjugate gradient with preconditioner P,
+// stopping once the relative residual is below C::TOL
+pub struct ConjugateGradient<P: Preconditioner<Mat>, C: Convergence> {
+    p: PhantomData<*const P>,
+    c: PhantomData<*const C>,
+}
+
+impl<P: Preconditioner<Mat>, C: Convergence> MatEqnSolver for ConjugateGradient<P, C> {
+    fn solve(m: Mat, b: Vec<f64>) -> Result<Vec<f64>, ()> {
+        conjugate_gradient::<Mat, P>(&m, &b, C::TOL, C::MAX_ITER)
+    }
+}
+
+// GMRES restarted every 30 iterations with preconditioner P,
+// stopping once the relative residual is below C::TOL
+pub struct Gmres<P: Preconditioner<Mat>, C: Convergence> {
+    p: PhantomData<*const P>,
+    c: PhantomData<*const C>,
+}
+
+impl<P: Preconditioner<Mat>, C: Convergence> MatEqnSolver for Gmres<P, C> {
+    fn solve(m: Mat, b: Vec<f64>) -> Result<Vec<f64>, ()> {
+        const RESTART: usize = 30;
+
+        gmres::<Mat, P>(&m, &b, RESTART, C::TOL, C::MAX_ITER)
+    }
+}
@@ -0,0 +1,107 @@
+use crate::mat::Mat;
+use crate::mat_eqn_solver::{back_sub, MatEqnSolver};
+use crate::norm::L2;
+use crate::qr_dec::QrDec;
+use std::marker::PhantomData;
+
+// trait representing a method to find the x minimizing ||Ax - b||_2
+// for an overdetermined (rows >= cols) system, returning x
+// along with the residual norm ||Ax - b||_2
+pub trait LeastSquares {
+    fn solve_lsq(m: Mat, b: Vec<f64>) -> Result<(Vec<f64>, f64), ()>;
+}
+
+// decompose A = QR with Q, then solve Rx = Q^T b
+pub struct QrLeastSquares<Q: QrDec> {
+    q: PhantomData<*const Q>,
+}
+
+impl<Q: QrDec> LeastSquares for QrLeastSquares<Q> {
+    fn solve_lsq(m: Mat, b: Vec<f64>) -> Result<(Vec<f64>, f64), ()> {
+        assert!(m.rows() >= m.cols());
+        assert_eq!(m.rows(), b.len());
+        let (q, r) = Q::dec(m.clone())?;
+        // rank deficient, R is numerically singular. rounding leaves a tiny
+        // non-zero pivot rather than an exact zero, so compare to the largest
+        let r_max = (0..r.rows()).map(|i| r.get(i, i).abs()).fold(0.0, f64::max);
+        let tol = (m.rows().max(m.cols()) as f64) * f64::EPSILON * r_max;
+        if (0..r.rows()).any(|i| r.get(i, i).abs() <= tol) {
+            return Err(());
+        }
+        let qtb = &q.transpose() * &b;
+        let x = back_sub(&r, &qtb);
+        let res_norm = m.residual(&x, &b).norm::<L2>();
+        Ok((x, res_norm))
+    }
+}
+
+impl<Q: QrDec> MatEqnSolver for QrLeastSquares<Q> {
+    fn solve(m: Mat, b: Vec<f64>) -> Result<Vec<f64>, ()> {
+        Self::solve_lsq(m, b).map(|(x, _)| x)
+    }
+}
+
+// solve the normal equations A^T A x = A^T b with S.
+// A^T A is symmetric positive definite when A has full column rank,
+// so S is typically LuDecompSolver<Cholesky>. squares the condition number
+pub struct NormalEquations<S: MatEqnSolver> {
+    s: PhantomData<*const S>,
+}
+
+impl<S: MatEqnSolver> LeastSquares for NormalEquations<S> {
+    fn solve_lsq(m: Mat, b: Vec<f64>) -> Result<(Vec<f64>, f64), ()> {
+        assert!(m.rows() >= m.cols());
+        assert_eq!(m.rows(), b.len());
+        let mt = m.transpose();
+        let x = S::solve(&mt * &m, &mt * &b)?;
+        let res_norm = m.residual(&x, &b).norm::<L2>();
+        Ok((x, res_norm))
+    }
+}
+
+impl<S: MatEqnSolver> MatEqnSolver for NormalEquations<S> {
+    fn solve(m: Mat, b: Vec<f64>) -> Result<Vec<f64>, ()> {
+        Self::solve_lsq(m, b).map(|(x, _)| x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::qr_dec::{Givens, Householder, ModifiedGramSchmidt};
+
+    fn mat(rows: usize, cols: usize, values: &[f64]) -> Mat {
+        let mut m = Mat::new(rows, cols);
+        for (i, v) in values.iter().enumerate() {
+            m.set(i / cols, i % cols, *v);
+        }
+        m
+    }
+
+    #[test]
+    fn rank_deficient_is_an_error() {
+        let m = mat(3, 2, &[1., 3., 2., 6., 3., 9.]);
+        let b = vec![1., 2., 4.];
+        assert!(QrLeastSquares::<Householder>::solve_lsq(m.clone(), b.clone()).is_err());
+        assert!(QrLeastSquares::<Givens>::solve_lsq(m.clone(), b.clone()).is_err());
+        assert!(QrLeastSquares::<ModifiedGramSchmidt>::solve_lsq(m, b).is_err());
+    }
+
+    #[test]
+    fn qr_variants_agree() {
+        let m = mat(3, 2, &[1., 3.5, 2., 6., 3., 9.]);
+        let b = vec![1., 2., 4.];
+        let (x, res) = QrLeastSquares::<Householder>::solve_lsq(m.clone(), b.clone()).unwrap();
+        for (y, res_y) in [
+            QrLeastSquares::<Givens>::solve_lsq(m.clone(), b.clone()).unwrap(),
+            QrLeastSquares::<ModifiedGramSchmidt>::solve_lsq(m, b).unwrap(),
+        ]
+        .iter()
+        {
+            assert!((res - res_y).abs() < 1e-12);
+            for (a, b) in x.iter().zip(y.iter()) {
+                assert!((a - b).abs() < 1e-10);
+            }
+        }
+    }
+}
@@ -0,0 +1,31 @@
+// algorithms report failure with a plain `Err(())`
+#![allow(clippy::result_unit_err)]
+
+pub mod banded;
+pub mod cond;
+pub mod eigenvalue;
+pub mod format;
+pub mod invert;
+pub mod io;
+pub mod iterative;
+pub mod krylov;
+pub mod least_squares;
+pub mod linear_operator;
+pub mod lu_dec;
+pub mod mat;
+pub mod mat_eqn_solver;
+pub mod norm;
+pub mod npy;
+pub mod op_log;
+pub mod qr_dec;
+pub mod reduce_upper;
+pub mod refine;
+pub mod rref;
+pub mod serialize;
+pub mod sparse;
+pub mod svd;
+pub mod sym_mat;
+pub mod trace;
+pub mod triangular;
+pub mod upper_triangle;
+pub mod vector;
@@ -0,0 +1,47 @@
+use crate::mat::Mat;
+use crate::vector::Vector;
+
+// trait representing a matrix that is only accessed through products,
+// which is all the iterative solvers and the power method need
+pub trait LinearOperator {
+    // number of rows in the operator
+    fn rows(&self) -> usize;
+
+    // number of columns in the operator
+    fn cols(&self) -> usize;
+
+    // calculate Ax
+    fn mul_vec(&self, x: &[f64]) -> Vector;
+
+    // calculate the dot product of row r with x
+    fn row_dot(&self, r: usize, x: &[f64]) -> f64;
+
+    // get a diagonal entry
+    fn diag(&self, i: usize) -> f64;
+}
+
+impl LinearOperator for Mat {
+    fn rows(&self) -> usize {
+        Mat::rows(self)
+    }
+
+    fn cols(&self) -> usize {
+        Mat::cols(self)
+    }
+
+    fn mul_vec(&self, x: &[f64]) -> Vector {
+        assert_eq!(Mat::cols(self), x.len());
+        (0..Mat::rows(self)).map(|r| self.row_dot(r, x)).collect()
+    }
+
+    fn row_dot(&self, r: usize, x: &[f64]) -> f64 {
+        self.iter_row(r)
+            .zip(x.iter())
+            .map(|(v1, v2)| *v1 * *v2)
+            .sum()
+    }
+
+    fn diag(&self, i: usize) -> f64 {
+        self.get(i, i)
+    }
+}
@@ -1,34 +1,120 @@
 use crate::mat::{Mat, RowOperation};
+use crate::triangular::{LowerTriangular, UpperTriangular};
 use crate::upper_triangle::UpperTriangle;
 use std::marker::PhantomData;
 
 // trait representing a method of LU decomposition
 pub trait LuDec {
-    fn dec(m: Mat) -> Result<(Mat, Mat), ()>;
+    fn dec(m: Mat) -> Result<(LowerTriangular, UpperTriangular), ()>;
+}
+
+// trait representing a method of LU decomposition with row pivoting,
+// PA = LU. the permutation is returned as the row of A that ends up
+// in each row of PA
+pub trait PivotedLuDec {
+    fn dec(m: Mat) -> Result<(Vec<usize>, LowerTriangular, UpperTriangular), ()>;
+}
+
+// any LU decomposition is a pivoted one that never swaps
+impl<D: LuDec> PivotedLuDec for D {
+    fn dec(m: Mat) -> Result<(Vec<usize>, LowerTriangular, UpperTriangular), ()> {
+        let n = m.rows();
+        let (l, u) = <D as LuDec>::dec(m)?;
+        Ok(((0..n).collect(), l, u))
+    }
 }
 
 // doolittle algorithm to calculate LU decomposition,
-// utilizing given UpperTriangle formula
+// utilizing given UpperTriangle formula. fails if the formula swaps or
+// scales a row, since L and U would then no longer multiply back to A
 pub struct Doolittle<T: UpperTriangle> {
     t: PhantomData<*const T>,
 }
 
 impl<T: UpperTriangle> LuDec for Doolittle<T> {
-    fn dec(mut u: Mat) -> Result<(Mat, Mat), ()> {
+    fn dec(mut u: Mat) -> Result<(LowerTriangular, UpperTriangular), ()> {
         assert!(u.is_square());
-        let mut l = Mat::new_i(u.rows());
+        let mut l = LowerTriangular::new_unit(u.rows());
+        let mut unsupported = false;
         // run the UpperTriangle formula,
         // executing the closure every time a row operation is performed
         T::run(&mut u, &mut |op| match *op {
             RowOperation::Cmb { src, scale, dest } => l.set(dest, src, scale),
-            RowOperation::Swap(r1, r2) => eprintln!(
-                "tried to swap R{} and R{}, but swapping is not supported.",
-                r1, r2
-            ),
+            RowOperation::Swap(..) | RowOperation::Scale { .. } => unsupported = true,
+        })?;
+        if unsupported {
+            // use PivotedDoolittle for a formula that swaps rows
+            return Err(());
+        }
+        Ok((l, UpperTriangular::from(&u)))
+    }
+}
+
+// cholesky decomposition A = L * L^T, for symmetric positive definite
+// matrices. fails if the matrix is not positive definite
+pub struct Cholesky;
+
+impl LuDec for Cholesky {
+    fn dec(m: Mat) -> Result<(LowerTriangular, UpperTriangular), ()> {
+        assert!(m.is_square());
+        let l = cholesky(m.rows(), |i, j| m.get(i, j))?;
+        let lt = l.transpose();
+        Ok((l, lt))
+    }
+}
+
+// cholesky factor L of an n x n symmetric matrix, reading only the
+// entries get(i, j) with i >= j. fails if it is not positive definite
+pub(crate) fn cholesky<G: Fn(usize, usize) -> f64>(
+    n: usize,
+    get: G,
+) -> Result<LowerTriangular, ()> {
+    let mut l = LowerTriangular::new(n);
+    for j in 0..n {
+        let d = get(j, j) - (0..j).map(|k| l.get(j, k) * l.get(j, k)).sum::<f64>();
+        if d <= 0.0 || !d.is_finite() {
+            return Err(());
+        }
+        let d = d.sqrt();
+        l.set(j, j, d);
+        for i in (j + 1)..n {
+            let v = get(i, j) - (0..j).map(|k| l.get(i, k) * l.get(j, k)).sum::<f64>();
+            l.set(i, j, v / d);
+        }
+    }
+    Ok(l)
+}
+
+// doolittle algorithm allowing the UpperTriangle formula to swap rows,
+// computing PA = LU
+pub struct PivotedDoolittle<T: UpperTriangle> {
+    t: PhantomData<*const T>,
+}
+
+impl<T: UpperTriangle> PivotedLuDec for PivotedDoolittle<T> {
+    fn dec(mut u: Mat) -> Result<(Vec<usize>, LowerTriangular, UpperTriangular), ()> {
+        assert!(u.is_square());
+        let n = u.rows();
+        let mut perm: Vec<usize> = (0..n).collect();
+        // multipliers are kept dense, so a swap can move the
+        // multipliers already found along with the rest of the row
+        let mut multipliers = Mat::new(n, n);
+        T::run(&mut u, &mut |op| match *op {
+            RowOperation::Cmb { src, scale, dest } => multipliers.set(dest, src, scale),
+            RowOperation::Swap(r1, r2) => {
+                perm.swap(r1, r2);
+                multipliers.apply(op);
+            }
             RowOperation::Scale { row, .. } => {
                 eprintln!("tried to scale R{}, but scaling is not supported", row)
             }
         })?;
-        Ok((l, u))
+        let mut l = LowerTriangular::new_unit(n);
+        for r in 0..n {
+            for c in 0..r {
+                l.set(r, c, multipliers.get(r, c));
+            }
+        }
+        Ok((perm, l, UpperTriangular::from(&u)))
     }
 }
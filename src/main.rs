@@ -1,21 +1,19 @@
-mod eigenvalue;
-mod invert;
-mod lu_dec;
-mod mat;
-mod mat_eqn_solver;
-mod norm;
-mod reduce_upper;
-mod upper_triangle;
-
-use crate::eigenvalue::{EigenSolve, InversePowerMethod, PowerMethod};
-use crate::invert::{AugmentedMat, Invert};
-use crate::lu_dec::{Doolittle, LuDec};
-use crate::mat::Mat;
-use crate::mat_eqn_solver::LuDecompSolver;
-use crate::norm::LInf;
-use crate::reduce_upper::BasicReduceUpper;
-use crate::upper_triangle::Gaussian;
+use math166_assignment2::cond::{Hager, LuCondEst};
+use math166_assignment2::eigenvalue::{EigenSolve, InversePowerMethod, PowerMethod, QrAlgorithm};
+use math166_assignment2::invert::{AugmentedMat, Invert, LuInvert};
+use math166_assignment2::lu_dec::{Doolittle, LuDec};
+use math166_assignment2::mat::Mat;
+use math166_assignment2::mat_eqn_solver::LuDecompSolver;
+use math166_assignment2::norm::LInf;
+use math166_assignment2::qr_dec::Householder;
+use math166_assignment2::reduce_upper::BasicReduceUpper;
+use math166_assignment2::svd::OneSidedJacobi;
+use math166_assignment2::sym_mat::SymMat;
+use math166_assignment2::upper_triangle::Gaussian;
 use rand::random;
+use std::process;
+
+mod cli;
 
 // convert a 2d array into a matrix
 fn to_mat<A: AsRef<[f64]>, R: AsRef<[A]>>(v2: R) -> Mat {
@@ -33,6 +31,20 @@ fn to_mat<A: AsRef<[f64]>, R: AsRef<[A]>>(v2: R) -> Mat {
 }
 
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.is_empty() {
+        assignment();
+    } else if args[0] == "-h" || args[0] == "--help" || args[0] == "help" {
+        print!("{}", cli::USAGE);
+    } else if let Err(e) = cli::run(&args) {
+        eprintln!("error: {}", e);
+        eprintln!("run with --help for usage");
+        process::exit(1);
+    }
+}
+
+// run the assignment problems
+fn assignment() {
     let mut m = Mat::new(3, 3);
     m.set(0, 0, 1.0);
     m.set(0, 1, 1.0);
@@ -71,6 +83,7 @@ fn main() {
         .expect("error while inverting matrix");
     println!("inverse matrix:");
     println!("{}", mp);
+    println!("det(A) = {}", m.det::<Gaussian>());
     println!("A * A^-1");
     println!("{}", &m * &mp);
 
@@ -93,6 +106,15 @@ fn main() {
         let cond = h.k::<LInf>(&h_inv);
         println!("K(h) = {}", cond);
 
+        let cond_est = LuCondEst::<Hager, Doolittle<Gaussian>>::estimate(&h)
+            .expect("error estimating condition number");
+        println!("K(h) estimate from LU (no inverse) = {}", cond_est);
+
+        let cond2 = h
+            .cond2::<OneSidedJacobi>()
+            .expect("error computing singular values");
+        println!("K_2(h) = {}", cond2);
+
         let (l, u) =
             Doolittle::<Gaussian>::dec(h.clone()).expect("error while computing LU decomposition");
         println!("L");
@@ -101,10 +123,8 @@ fn main() {
         println!("U");
         println!("{}", u);
 
-        let l_inv = AugmentedMat::<Gaussian, BasicReduceUpper>::invert(l.clone())
-            .expect("error computing inverse");
-        let u_inv = AugmentedMat::<Gaussian, BasicReduceUpper>::invert(u.clone())
-            .expect("error computing inverse");
+        let l_inv = l.invert().expect("error computing inverse");
+        let u_inv = u.invert().expect("error computing inverse");
 
         let u_inv_l_inv = &u_inv * &l_inv;
         println!("Hilbert Matrix ({})-1 (U^-1 * L^-1)", k);
@@ -116,6 +136,14 @@ fn main() {
             diff.max()
         );
 
+        let h_lu_inv =
+            LuInvert::<Doolittle<Gaussian>>::invert(h.clone()).expect("error computing inverse");
+        let diff = &h_lu_inv - &h_inv;
+        println!(
+            "maximum difference in a term of inverse matrices (LU substitution) = {}",
+            diff.max()
+        );
+
         println!();
     }
 
@@ -268,7 +296,7 @@ fn main() {
             InversePowerMethod::<LuDecompSolver<Doolittle<Gaussian>>>::eigen_solve(&mc, q, 1e-10)
                 .expect("error computing eigenvalue");
 
-        if let None = results.iter().filter(|v| f64::abs(*v - e) < 0.1).next() {
+        if !results.iter().any(|v| f64::abs(*v - e) < 0.1) {
             results.push(e);
         }
         q += 0.13;
@@ -279,4 +307,21 @@ fn main() {
         print!("{:.9}, ", e);
     }
     println!();
+
+    let results = SymMat::from_mat(&mc)
+        .and_then(|s| s.eigenvalues(1e-10))
+        .expect("error computing eigenvalues using the jacobi eigenvalue method");
+    print!("eigenvalues using the jacobi eigenvalue method: ");
+    for e in results {
+        print!("{:.9}, ", e);
+    }
+    println!();
+
+    let results = QrAlgorithm::<Householder>::eigenvalues(&mc, 1e-10)
+        .expect("error computing eigenvalues using the QR algorithm");
+    print!("eigenvalues using the QR algorithm: ");
+    for e in results {
+        print!("{:.9}, ", e);
+    }
+    println!();
 }
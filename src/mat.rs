@@ -1,4 +1,6 @@
+use crate::format::NumFormat;
 use crate::norm::Norm;
+use crate::upper_triangle::UpperTriangle;
 use std::fmt;
 use std::ops::{Mul, Sub};
 
@@ -10,6 +12,41 @@ pub enum RowOperation {
     Scale { row: usize, scale: f64 },
 }
 
+impl RowOperation {
+    // the row operation that undoes this one
+    pub fn inverse(&self) -> RowOperation {
+        match *self {
+            RowOperation::Swap(r1, r2) => RowOperation::Swap(r1, r2),
+            RowOperation::Cmb { src, scale, dest } => RowOperation::Cmb {
+                src,
+                scale: -scale,
+                dest,
+            },
+            RowOperation::Scale { row, scale } => RowOperation::Scale {
+                row,
+                scale: 1.0 / scale,
+            },
+        }
+    }
+
+    // the elementary matrix E of size n x n, such that
+    // applying this row operation to A is the same as E * A
+    pub fn elementary(&self, n: usize) -> Mat {
+        let mut res = Mat::new_i(n);
+        res.apply(self);
+        res
+    }
+
+    // apply the row operation to a vector
+    pub fn apply_vec(&self, v: &mut [f64]) {
+        match *self {
+            RowOperation::Swap(r1, r2) => v.swap(r1, r2),
+            RowOperation::Cmb { src, scale, dest } => v[dest] -= scale * v[src],
+            RowOperation::Scale { row, scale } => v[row] *= scale,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 // matrix, represented by an array on the heap
 pub struct Mat {
@@ -92,6 +129,17 @@ impl Mat {
         }
     }
 
+    // construct the transpose of the matrix
+    pub fn transpose(&self) -> Self {
+        let mut res = Mat::new(self.cols(), self.rows());
+        for r in 0..self.rows() {
+            for c in 0..self.cols() {
+                res.set(c, r, self.get(r, c));
+            }
+        }
+        res
+    }
+
     // is the matrix square?
     pub fn is_square(&self) -> bool {
         self.rows() == self.cols()
@@ -135,7 +183,60 @@ impl Mat {
 
     // calculate k for the matrix, given its inverse
     pub fn k<N: Norm>(&self, inv: &Mat) -> f64 {
-        N::norm(self) * N::norm(&inv)
+        N::norm(self) * N::norm(inv)
+    }
+
+    // calculate the determinant of the matrix, reducing it with T and
+    // multiplying the pivots. singular matrices produce 0
+    pub fn det<T: UpperTriangle>(&self) -> f64 {
+        assert!(self.is_square());
+        let mut m = self.clone();
+        let mut det = 1.0;
+        // undo the change each row operation makes to the determinant,
+        // as in log_det
+        let res = T::run(&mut m, &mut |op| match *op {
+            RowOperation::Swap(..) => det = -det,
+            RowOperation::Scale { scale, .. } => det /= scale,
+            RowOperation::Cmb { .. } => {}
+        });
+        if res.is_err() {
+            return 0.0;
+        }
+        (0..m.rows()).fold(det, |det, i| det * m.get(i, i))
+    }
+
+    // calculate the sign and natural log of the magnitude of the
+    // determinant, reducing the matrix with T. suitable for large
+    // matrices whose determinant would overflow or underflow an f64.
+    // singular matrices produce (0, -inf)
+    pub fn log_det<T: UpperTriangle>(&self) -> (f64, f64) {
+        assert!(self.is_square());
+        let mut m = self.clone();
+        let mut sign = 1.0;
+        let mut log_abs = 0.0;
+        // every row operation changes the determinant in a known way:
+        // swaps negate it, scales multiply it, combinations leave it alone.
+        // undo each change so we are left with the determinant of the input
+        let res = T::run(&mut m, &mut |op| match *op {
+            RowOperation::Swap(..) => sign = -sign,
+            RowOperation::Scale { scale, .. } => {
+                sign *= scale.signum();
+                log_abs -= f64::ln(scale.abs());
+            }
+            RowOperation::Cmb { .. } => {}
+        });
+        if res.is_err() {
+            return (0.0, -1. / 0.);
+        }
+        for i in 0..m.rows() {
+            let p = m.get(i, i);
+            if p == 0.0 {
+                return (0.0, -1. / 0.);
+            }
+            sign *= p.signum();
+            log_abs += f64::ln(p.abs());
+        }
+        (sign, log_abs)
     }
 
     // maximum magnitude term in matrix
@@ -146,22 +247,28 @@ impl Mat {
             .fold(-1. / 0., f64::max)
     }
 
-    // pretty print the matrix
-    fn display(&self, n_chars: usize, f: &mut fmt::Formatter) -> fmt::Result {
-        fn f64_fmt_len(i: f64, len: usize) -> String {
-            let mut res = format!("{:.64}", i);
-            while res.len() < len {
-                res.push(' ');
-            }
-            res[0..len].to_string()
-        }
-
-        let line_len = (((n_chars + 3) * self.cols()) as isize + -1) as usize;
+    // pretty print the matrix, with every column right aligned
+    // and at least min_width characters wide
+    fn display(&self, fmt: NumFormat, min_width: usize, f: &mut fmt::Formatter) -> fmt::Result {
+        let cells: Vec<Vec<String>> = self
+            .iter()
+            .map(|row| row.iter().map(|v| fmt.format(*v)).collect())
+            .collect();
+        let widths: Vec<usize> = (0..self.cols())
+            .map(|c| {
+                cells
+                    .iter()
+                    .map(|row| row[c].len())
+                    .fold(min_width, usize::max)
+            })
+            .collect();
+
+        let line_len = widths.iter().map(|w| w + 3).sum::<usize>() - 1;
         writeln!(f, "+{}+", "-".repeat(line_len))?;
-        for r in self.iter() {
+        for row in cells.iter() {
             write!(f, "| ")?;
-            for v in r.iter() {
-                write!(f, "{} | ", f64_fmt_len(*v, n_chars))?;
+            for (v, w) in row.iter().zip(widths.iter()) {
+                write!(f, "{:>w$} | ", v, w = w)?;
             }
             writeln!(f)?;
         }
@@ -189,15 +296,14 @@ impl Mul<&Vec<f64>> for &Mat {
 
     fn mul(self, rhs: &Vec<f64>) -> <Self as Mul<&Vec<f64>>>::Output {
         assert_eq!(self.cols(), rhs.len());
-        let mut res = vec![0.0; self.rows()];
-        for r in 0..self.rows() {
-            res[r] = rhs
-                .iter()
-                .zip(self.iter_row(r))
-                .map(|(v1, v2)| *v1 * *v2)
-                .sum();
-        }
-        res
+        (0..self.rows())
+            .map(|r| {
+                rhs.iter()
+                    .zip(self.iter_row(r))
+                    .map(|(v1, v2)| *v1 * *v2)
+                    .sum()
+            })
+            .collect()
     }
 }
 
@@ -245,7 +351,50 @@ impl Sub<&Mat> for &Mat {
 // allow matrices to be printed by println
 impl fmt::Display for Mat {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        const N_CHARS: usize = 10;
-        self.display(N_CHARS, f)
+        // {:8.3} prints 3 significant digits in columns at least 8 wide
+        const PRECISION: usize = 6;
+        let precision = f.precision().unwrap_or(PRECISION);
+        let min_width = f.width().unwrap_or(0);
+        self.display(NumFormat::significant(precision), min_width, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::upper_triangle::Gaussian;
+
+    #[test]
+    fn det_of_integer_diagonal_is_exact() {
+        for a in 1..=11 {
+            for b in 1..=11 {
+                let mut m = Mat::new(2, 2);
+                m.set(0, 0, a as f64);
+                m.set(1, 1, b as f64);
+                assert_eq!(m.det::<Gaussian>(), (a * b) as f64);
+            }
+        }
+    }
+
+    #[test]
+    fn det_of_small_integer_matrices() {
+        let mut m = Mat::new(3, 3);
+        for (i, v) in [1., 1., 1., 0., 2., 5., 2., 5., -1.].iter().enumerate() {
+            m.set(i / 3, i % 3, *v);
+        }
+        assert_eq!(m.det::<Gaussian>(), -21.0);
+
+        // a zero pivot forces a swap, negating the determinant
+        let mut p = Mat::new(2, 2);
+        p.set(0, 1, 1.0);
+        p.set(1, 0, 1.0);
+        assert_eq!(p.det::<Gaussian>(), -1.0);
+
+        let mut s = Mat::new(2, 2);
+        s.set(0, 0, 1.0);
+        s.set(0, 1, 2.0);
+        s.set(1, 0, 2.0);
+        s.set(1, 1, 4.0);
+        assert_eq!(s.det::<Gaussian>(), 0.0);
     }
 }
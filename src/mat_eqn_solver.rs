@@ -1,5 +1,5 @@
-use crate::lu_dec::LuDec;
-use crate::mat::{Mat, RowOperation};
+use crate::lu_dec::{LuDec, PivotedDoolittle, PivotedLuDec};
+use crate::mat::Mat;
 use crate::upper_triangle::UpperTriangle;
 use std::marker::PhantomData;
 
@@ -20,11 +20,7 @@ where
 {
     fn solve(mut m: Mat, mut b: Vec<f64>) -> Result<Vec<f64>, ()> {
         assert_eq!(m.cols(), b.len());
-        let res = T::run(&mut m, &mut |op| match *op {
-            RowOperation::Swap(r1, r2) => b.swap(r1, r2),
-            RowOperation::Cmb { src, scale, dest } => b[dest] -= scale * b[src],
-            RowOperation::Scale { row, scale } => b[row] *= scale,
-        });
+        let res = T::run(&mut m, &mut |op| op.apply_vec(&mut b));
         if res.is_err() {
             return Err(());
         }
@@ -47,19 +43,44 @@ pub struct LuDecompSolver<D: LuDec> {
 impl<D: LuDec> MatEqnSolver for LuDecompSolver<D> {
     fn solve(m: Mat, b: Vec<f64>) -> Result<Vec<f64>, ()> {
         let (l, u) = D::dec(m)?;
+        u.solve(&l.solve(&b)?)
+    }
+}
 
-        let n = l.rows() - 1;
-        let mut y = vec![0.0; l.rows()];
-        y[0] = b[0] / l.get(0, 0);
-        for i in 1..=n {
-            y[i] = (b[i] - (0..i).map(|j| l.get(i, j) * y[j]).sum::<f64>()) / l.get(i, i);
-        }
+// convert the matrix into PA = LU form, allowing T to swap rows,
+// then solve LUx = Pb
+pub struct PivotedLuSolver<T: UpperTriangle> {
+    t: PhantomData<*const T>,
+}
 
-        let mut x = vec![0.0; u.rows()];
-        x[n] = y[n] / u.get(n, n);
-        for i in (0..=n).rev() {
-            x[i] = (y[i] - ((i + 1)..=n).map(|j| u.get(i, j) * x[j]).sum::<f64>()) / u.get(i, i);
-        }
-        Ok(x)
+impl<T: UpperTriangle> MatEqnSolver for PivotedLuSolver<T> {
+    fn solve(m: Mat, b: Vec<f64>) -> Result<Vec<f64>, ()> {
+        assert_eq!(m.rows(), b.len());
+        let (perm, l, u) = PivotedDoolittle::<T>::dec(m)?;
+        let pb: Vec<f64> = perm.iter().map(|r| b[*r]).collect();
+        u.solve(&l.solve(&pb)?)
+    }
+}
+
+// solve Lx = b, where L is lower triangular
+pub fn forward_sub(l: &Mat, b: &[f64]) -> Vec<f64> {
+    assert!(l.is_square());
+    assert_eq!(l.rows(), b.len());
+    let mut x = vec![0.0; l.rows()];
+    for i in 0..l.rows() {
+        x[i] = (b[i] - (0..i).map(|j| l.get(i, j) * x[j]).sum::<f64>()) / l.get(i, i);
+    }
+    x
+}
+
+// solve Ux = b, where U is upper triangular
+pub fn back_sub(u: &Mat, b: &[f64]) -> Vec<f64> {
+    assert!(u.is_square());
+    assert_eq!(u.rows(), b.len());
+    let n = u.rows() - 1;
+    let mut x = vec![0.0; u.rows()];
+    for i in (0..=n).rev() {
+        x[i] = (b[i] - ((i + 1)..=n).map(|j| u.get(i, j) * x[j]).sum::<f64>()) / u.get(i, i);
     }
+    x
 }
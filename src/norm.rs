@@ -5,10 +5,34 @@ pub trait Norm {
     fn norm(m: &Mat) -> f64;
 }
 
-// l infinity norm
+// l infinity norm (maximum absolute row sum)
 pub struct LInf;
 
 impl Norm for LInf {
+    fn norm(m: &Mat) -> f64 {
+        let mut res = 0.0;
+        for r in 0..m.rows() {
+            let mut curr = 0.0;
+            for c in 0..m.cols() {
+                curr += f64::abs(m.get(r, c));
+            }
+            if curr > res {
+                res = curr;
+            }
+        }
+        res
+    }
+}
+
+// trait representing a method to calculate a norm of a vector
+pub trait VecNorm {
+    fn vec_norm(v: &[f64]) -> f64;
+}
+
+// l1 norm (maximum absolute column sum)
+pub struct L1;
+
+impl Norm for L1 {
     fn norm(m: &Mat) -> f64 {
         let mut res = 0.0;
         for c in 0..m.cols() {
@@ -23,3 +47,49 @@ impl Norm for LInf {
         res
     }
 }
+
+// l2 (euclidean) norm. the matrix 2-norm needs an SVD, which can
+// fail to converge, so it is Mat::norm2 rather than a Norm
+pub struct L2;
+
+impl VecNorm for L1 {
+    fn vec_norm(v: &[f64]) -> f64 {
+        v.iter().map(|x| f64::abs(*x)).sum()
+    }
+}
+
+impl VecNorm for L2 {
+    fn vec_norm(v: &[f64]) -> f64 {
+        // scale by the largest magnitude to avoid overflow / underflow
+        let scale = LInf::vec_norm(v);
+        // an infinite scale would give inf / inf = NaN
+        if scale == 0.0 || scale.is_infinite() {
+            return scale;
+        }
+        scale
+            * v.iter()
+                .map(|x| (x / scale) * (x / scale))
+                .sum::<f64>()
+                .sqrt()
+    }
+}
+
+impl VecNorm for LInf {
+    fn vec_norm(v: &[f64]) -> f64 {
+        v.iter().map(|x| f64::abs(*x)).fold(0.0, f64::max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn l2_vec_norm() {
+        assert_eq!(L2::vec_norm(&[3., -4.]), 5.0);
+        assert_eq!(L2::vec_norm(&[0., 0.]), 0.0);
+        assert_eq!(L2::vec_norm(&[1e300, 1e300]), 1e300 * f64::sqrt(2.));
+        assert_eq!(L2::vec_norm(&[1., f64::INFINITY]), f64::INFINITY);
+        assert_eq!(L2::vec_norm(&[f64::NEG_INFINITY, 1.]), f64::INFINITY);
+    }
+}
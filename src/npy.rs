@@ -0,0 +1,225 @@
+use crate::io::{checked_len, ReadError};
+use crate::mat::Mat;
+use std::io::{self, Read, Write};
+
+// every .npy file starts with these bytes, followed by the format version
+const MAGIC: &[u8; 6] = b"\x93NUMPY";
+
+// longest header read, the same limit numpy itself uses by default.
+// version 2 and 3 lengths are 32 bit, so must be checked before allocating
+const MAX_HEADER_LEN: usize = 10000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+// order the values of a matrix are stored in
+pub enum Order {
+    // row by row, numpy's default
+    C,
+    // column by column
+    Fortran,
+}
+
+// write a matrix as a version 1.0 .npy file of little endian f64
+pub fn write_npy<W: Write>(m: &Mat, mut w: W, order: Order) -> io::Result<()> {
+    let fortran = match order {
+        Order::C => "False",
+        Order::Fortran => "True",
+    };
+    let mut header = format!(
+        "{{'descr': '<f8', 'fortran_order': {}, 'shape': ({}, {}), }}",
+        fortran,
+        m.rows(),
+        m.cols()
+    );
+    // the header is padded with spaces and ends with a newline, so the
+    // data starts on a 64 byte boundary
+    let len = MAGIC.len() + 2 + 2 + header.len() + 1;
+    header.push_str(&" ".repeat((64 - len % 64) % 64));
+    header.push('\n');
+
+    w.write_all(MAGIC)?;
+    w.write_all(&[1, 0])?;
+    w.write_all(&(header.len() as u16).to_le_bytes())?;
+    w.write_all(header.as_bytes())?;
+    let values: Box<dyn Iterator<Item = f64>> = match order {
+        Order::C => Box::new(m.iter().flat_map(|row| row.iter().cloned())),
+        Order::Fortran => {
+            Box::new((0..m.cols()).flat_map(move |c| (0..m.rows()).map(move |r| m.get(r, c))))
+        }
+    };
+    for v in values {
+        w.write_all(&v.to_le_bytes())?;
+    }
+    Ok(())
+}
+
+// read a .npy file of f64 in either order. a one dimensional
+// array is read as a column vector
+pub fn read_npy<R: Read>(mut r: R) -> Result<Mat, ReadError> {
+    let mut magic = [0; 8];
+    r.read_exact(&mut magic)?;
+    if &magic[..6] != MAGIC {
+        return Err(ReadError::Format("not a .npy file".to_string()));
+    }
+    let header_len = match magic[6] {
+        1 => {
+            let mut buf = [0; 2];
+            r.read_exact(&mut buf)?;
+            u16::from_le_bytes(buf) as usize
+        }
+        2 | 3 => {
+            let mut buf = [0; 4];
+            r.read_exact(&mut buf)?;
+            u32::from_le_bytes(buf) as usize
+        }
+        v => {
+            return Err(ReadError::Format(format!(
+                "unsupported .npy version {}.{}",
+                v, magic[7]
+            )))
+        }
+    };
+    if header_len > MAX_HEADER_LEN {
+        return Err(ReadError::Format(format!(
+            "header of {} bytes is too long",
+            header_len
+        )));
+    }
+    let mut header = vec![0; header_len];
+    r.read_exact(&mut header)?;
+    let header = String::from_utf8(header)
+        .map_err(|_| ReadError::Format("header is not valid text".to_string()))?;
+
+    let descr = dict_value(&header, "descr")?;
+    let little_endian = match descr.trim_matches(['\'', '"']) {
+        "<f8" => true,
+        ">f8" => false,
+        d => {
+            return Err(ReadError::Format(format!(
+                "unsupported dtype {}, only f8 is supported",
+                d
+            )))
+        }
+    };
+    let order = match dict_value(&header, "fortran_order")? {
+        "False" => Order::C,
+        "True" => Order::Fortran,
+        v => return Err(ReadError::Format(format!("invalid fortran_order {}", v))),
+    };
+    let shape = dict_value(&header, "shape")?
+        .trim_start_matches('(')
+        .trim_end_matches(')')
+        .split(',')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            s.parse::<usize>()
+                .map_err(|_| ReadError::Format(format!("invalid dimension {:?}", s)))
+        })
+        .collect::<Result<Vec<usize>, _>>()?;
+    let (rows, cols) = match shape.as_slice() {
+        [n] => (*n, 1),
+        [rows, cols] => (*rows, *cols),
+        s => {
+            return Err(ReadError::Format(format!(
+                "expected 1 or 2 dimensions, found {}",
+                s.len()
+            )))
+        }
+    };
+    if rows == 0 || cols == 0 {
+        return Err(ReadError::Empty);
+    }
+    if checked_len(rows, cols).is_none() {
+        return Err(ReadError::Format(format!(
+            "a {} x {} matrix is too large",
+            rows, cols
+        )));
+    }
+
+    let mut res = Mat::new(rows, cols);
+    let mut buf = [0; 8];
+    for i in 0..rows * cols {
+        r.read_exact(&mut buf)?;
+        let v = if little_endian {
+            f64::from_le_bytes(buf)
+        } else {
+            f64::from_be_bytes(buf)
+        };
+        match order {
+            Order::C => res.set(i / cols, i % cols, v),
+            Order::Fortran => res.set(i % rows, i / rows, v),
+        }
+    }
+    Ok(res)
+}
+
+// the raw text of a value in the header dictionary, which numpy
+// writes as a python literal, e.g. {'descr': '<f8', 'shape': (3, 4), }
+fn dict_value<'a>(header: &'a str, key: &str) -> Result<&'a str, ReadError> {
+    let missing = || ReadError::Format(format!("header has no {:?}", key));
+    let start = header
+        .find(&format!("'{}'", key))
+        .or_else(|| header.find(&format!("\"{}\"", key)))
+        .ok_or_else(missing)?;
+    let rest = header[start + key.len() + 2..].trim_start();
+    let rest = rest.strip_prefix(':').ok_or_else(missing)?.trim_start();
+    // a tuple runs to its closing parenthesis, anything else to the next comma
+    let end = if rest.starts_with('(') {
+        rest.find(')').map(|i| i + 1)
+    } else {
+        rest.find([',', '}'])
+    };
+    Ok(rest[..end.unwrap_or(rest.len())].trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a version 1.0 file with the given header and no data
+    fn npy(header: &str) -> Vec<u8> {
+        let mut res = MAGIC.to_vec();
+        res.extend_from_slice(&[1, 0]);
+        res.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        res.extend_from_slice(header.as_bytes());
+        res
+    }
+
+    #[test]
+    fn huge_shapes_are_a_format_error() {
+        for shape in [
+            "(18446744073709551615, 2)",
+            "(4294967296, 4294967296)",
+            "(1000000, 1000000)",
+        ]
+        .iter()
+        {
+            let header = format!(
+                "{{'descr': '<f8', 'fortran_order': False, 'shape': {}, }}\n",
+                shape
+            );
+            let res = read_npy(&npy(&header)[..]);
+            assert!(matches!(res, Err(ReadError::Format(_))), "{}", shape);
+        }
+    }
+
+    #[test]
+    fn long_headers_are_rejected_before_allocating() {
+        let mut data = MAGIC.to_vec();
+        data.extend_from_slice(&[2, 0]);
+        data.extend_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(read_npy(&data[..]), Err(ReadError::Format(_))));
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut m = Mat::new(2, 3);
+        m.set(0, 2, 2.5);
+        m.set(1, 0, -1.0);
+        for order in [Order::C, Order::Fortran].iter() {
+            let mut buf = vec![];
+            write_npy(&m, &mut buf, *order).unwrap();
+            assert!(read_npy(&buf[..]).unwrap().iter().eq(m.iter()));
+        }
+    }
+}
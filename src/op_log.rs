@@ -0,0 +1,74 @@
+use crate::mat::{Mat, RowOperation};
+
+#[derive(Debug, Clone, Default)]
+// log of row operations, in the order they were performed
+pub struct OpLog {
+    ops: Vec<RowOperation>,
+}
+
+impl OpLog {
+    // construct a new empty log
+    pub fn new() -> Self {
+        OpLog { ops: vec![] }
+    }
+
+    // add a row operation to the end of the log
+    pub fn push(&mut self, op: &RowOperation) {
+        self.ops.push(op.clone());
+    }
+
+    // closure that records every row operation it is called with,
+    // for passing to UpperTriangle::run and ReduceUpper::run
+    pub fn recorder(&mut self) -> impl FnMut(&RowOperation) + '_ {
+        move |op| self.push(op)
+    }
+
+    // the recorded row operations
+    pub fn ops(&self) -> &[RowOperation] {
+        &self.ops
+    }
+
+    // number of recorded row operations
+    pub fn len(&self) -> usize {
+        self.ops.len()
+    }
+
+    // have no row operations been recorded?
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    // perform every recorded row operation on a matrix, in order
+    pub fn replay(&self, m: &mut Mat) {
+        for op in self.ops.iter() {
+            m.apply(op);
+        }
+    }
+
+    // perform every recorded row operation on a vector, in order
+    pub fn replay_vec(&self, v: &mut [f64]) {
+        for op in self.ops.iter() {
+            op.apply_vec(v);
+        }
+    }
+
+    // the log that undoes this one, each operation inverted in reverse order
+    pub fn inverse(&self) -> OpLog {
+        OpLog {
+            ops: self.ops.iter().rev().map(|op| op.inverse()).collect(),
+        }
+    }
+
+    // the elementary matrix of size n x n for every recorded row operation
+    pub fn elementary(&self, n: usize) -> Vec<Mat> {
+        self.ops.iter().map(|op| op.elementary(n)).collect()
+    }
+
+    // compose the recorded row operations into a single n x n transform
+    // T = E_k * ... * E_2 * E_1, so replaying the log on A gives T * A
+    pub fn transform(&self, n: usize) -> Mat {
+        let mut res = Mat::new_i(n);
+        self.replay(&mut res);
+        res
+    }
+}
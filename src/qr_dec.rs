@@ -0,0 +1,156 @@
+use crate::mat::Mat;
+use crate::norm::{VecNorm, L2};
+
+// trait representing a method of (reduced) QR decomposition.
+// for an m x n matrix with m >= n, Q is m x n with orthonormal
+// columns and R is n x n upper triangular
+pub trait QrDec {
+    fn dec(m: Mat) -> Result<(Mat, Mat), ()>;
+}
+
+// copy the top n x n block of m into a new upper triangular matrix
+fn top_r(m: &Mat) -> Mat {
+    let n = m.cols();
+    let mut r = Mat::new(n, n);
+    for i in 0..n {
+        for j in i..n {
+            r.set(i, j, m.get(i, j));
+        }
+    }
+    r
+}
+
+// apply the reflection (I - 2vv^T) to rows k.. of columns c0.. of m
+fn reflect(m: &mut Mat, v: &[f64], k: usize, c0: usize) {
+    for c in c0..m.cols() {
+        let d: f64 = (k..m.rows()).map(|i| v[i - k] * m.get(i, c)).sum();
+        for i in k..m.rows() {
+            m.set(i, c, m.get(i, c) - 2.0 * v[i - k] * d);
+        }
+    }
+}
+
+// householder reflections, zeroing each column below the diagonal at once
+pub struct Householder;
+
+impl QrDec for Householder {
+    fn dec(mut m: Mat) -> Result<(Mat, Mat), ()> {
+        assert!(m.rows() >= m.cols());
+        let rows = m.rows();
+        let cols = m.cols();
+        let mut vs = Vec::with_capacity(cols);
+        for k in 0..cols {
+            // reflect column k below the diagonal onto the k-th axis
+            let mut v: Vec<f64> = (k..rows).map(|i| m.get(i, k)).collect();
+            let x_norm = L2::vec_norm(&v);
+            let alpha = if v[0] > 0.0 { -x_norm } else { x_norm };
+            v[0] -= alpha;
+            let v_norm = L2::vec_norm(&v);
+            if v_norm != 0.0 {
+                for vi in v.iter_mut() {
+                    *vi /= v_norm;
+                }
+                reflect(&mut m, &v, k, k);
+            }
+            vs.push(v);
+        }
+
+        // Q = H_0 H_1 ... H_n-1 applied to the first n columns of I
+        let mut q = Mat::new(rows, cols);
+        for i in 0..cols {
+            q.set(i, i, 1.0);
+        }
+        for (k, v) in vs.iter().enumerate().rev() {
+            reflect(&mut q, v, k, 0);
+        }
+        Ok((q, top_r(&m)))
+    }
+}
+
+// givens rotations, zeroing one entry below the diagonal at a time
+pub struct Givens;
+
+impl QrDec for Givens {
+    fn dec(mut m: Mat) -> Result<(Mat, Mat), ()> {
+        assert!(m.rows() >= m.cols());
+        let rows = m.rows();
+        let cols = m.cols();
+        // accumulate Q^T by applying every rotation to I
+        let mut qt = Mat::new_i(rows);
+        for j in 0..cols {
+            for i in ((j + 1)..rows).rev() {
+                let a = m.get(i - 1, j);
+                let b = m.get(i, j);
+                if b == 0.0 {
+                    continue;
+                }
+                let r = a.hypot(b);
+                let c = a / r;
+                let s = b / r;
+                for t in [&mut m, &mut qt].iter_mut() {
+                    for k in 0..t.cols() {
+                        let x = t.get(i - 1, k);
+                        let y = t.get(i, k);
+                        t.set(i - 1, k, c * x + s * y);
+                        t.set(i, k, -s * x + c * y);
+                    }
+                }
+            }
+        }
+
+        let mut q = Mat::new(rows, cols);
+        for i in 0..rows {
+            for j in 0..cols {
+                q.set(i, j, qt.get(j, i));
+            }
+        }
+        Ok((q, top_r(&m)))
+    }
+}
+
+// modified gram-schmidt, orthogonalizing the remaining columns against
+// each new column of Q as soon as it is produced. fails if the
+// columns of the matrix are (numerically) linearly dependent
+pub struct ModifiedGramSchmidt;
+
+impl QrDec for ModifiedGramSchmidt {
+    fn dec(m: Mat) -> Result<(Mat, Mat), ()> {
+        assert!(m.rows() >= m.cols());
+        let cols = m.cols();
+        let mut vs: Vec<Vec<f64>> = (0..cols)
+            .map(|c| m.iter_col(c).cloned().collect())
+            .collect();
+        // a dependent column is left with only rounding error once the
+        // previous columns are removed, so compare to the largest column
+        let col_max = vs.iter().map(|v| L2::vec_norm(v)).fold(0.0, f64::max);
+        let tol = (m.rows().max(cols) as f64) * f64::EPSILON * col_max;
+        let mut r = Mat::new(cols, cols);
+        for k in 0..cols {
+            let r_kk = L2::vec_norm(&vs[k]);
+            if r_kk <= tol {
+                return Err(());
+            }
+            r.set(k, k, r_kk);
+            for v in vs[k].iter_mut() {
+                *v /= r_kk;
+            }
+            let (done, rest) = vs.split_at_mut(k + 1);
+            let qk = &done[k];
+            for (j, v) in rest.iter_mut().enumerate() {
+                let r_kj: f64 = qk.iter().zip(v.iter()).map(|(a, b)| *a * *b).sum();
+                r.set(k, k + 1 + j, r_kj);
+                for (vi, qi) in v.iter_mut().zip(qk.iter()) {
+                    *vi -= r_kj * *qi;
+                }
+            }
+        }
+
+        let mut q = Mat::new(m.rows(), cols);
+        for (c, v) in vs.iter().enumerate() {
+            for (r, val) in v.iter().enumerate() {
+                q.set(r, c, *val);
+            }
+        }
+        Ok((q, r))
+    }
+}
@@ -0,0 +1,154 @@
+use crate::lu_dec::PivotedLuDec;
+use crate::mat::Mat;
+use crate::mat_eqn_solver::MatEqnSolver;
+use crate::norm::{LInf, Norm, VecNorm};
+use std::marker::PhantomData;
+
+// trait representing a method to calculate the residual b - Ax
+pub trait Residual {
+    fn residual(m: &Mat, x: &[f64], b: &[f64]) -> Vec<f64>;
+}
+
+// residual in working (f64) precision
+pub struct WorkingPrecision;
+
+impl Residual for WorkingPrecision {
+    fn residual(m: &Mat, x: &[f64], b: &[f64]) -> Vec<f64> {
+        m.residual(x, b).into_vec()
+    }
+}
+
+// residual accumulated in double-double (~106 bit) precision, using
+// fused multiply-add to capture the rounding error of every product and
+// two-sum to capture the rounding error of every addition
+pub struct ExtendedPrecision;
+
+impl Residual for ExtendedPrecision {
+    fn residual(m: &Mat, x: &[f64], b: &[f64]) -> Vec<f64> {
+        assert_eq!(m.cols(), x.len());
+        assert_eq!(m.rows(), b.len());
+        (0..m.rows())
+            .map(|r| {
+                let mut hi = b[r];
+                let mut lo = 0.0;
+                for (a, xv) in m.iter_row(r).zip(x.iter()) {
+                    let p = -*a * *xv;
+                    let p_err = (-*a).mul_add(*xv, -p);
+                    let (s, s_err) = two_sum(hi, p);
+                    hi = s;
+                    lo += s_err + p_err;
+                }
+                hi + lo
+            })
+            .collect()
+    }
+}
+
+// a + b = s + err exactly
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let s = a + b;
+    let bb = s - a;
+    let err = (a - (s - bb)) + (b - bb);
+    (s, err)
+}
+
+// factor PA = LU once with D, then repeatedly compute the residual
+// r = b - Ax with R, solve LUd = Pr reusing the factors, and correct
+// x = x + d until the corrections stop improving the solution.
+// D is any LuDec, or PivotedDoolittle to allow row swaps. fails if the
+// refined x still does not solve the system, e.g. when the factors are
+// too inaccurate for the corrections to converge
+pub struct IterativeRefinement<D: PivotedLuDec, R: Residual> {
+    d: PhantomData<*const D>,
+    r: PhantomData<*const R>,
+}
+
+impl<D: PivotedLuDec, R: Residual> MatEqnSolver for IterativeRefinement<D, R> {
+    fn solve(m: Mat, b: Vec<f64>) -> Result<Vec<f64>, ()> {
+        const MAX_ITER: usize = 10;
+
+        assert_eq!(m.rows(), b.len());
+        let (perm, l, u) = D::dec(m.clone())?;
+        let lu_solve = |v: &[f64]| {
+            let pv: Vec<f64> = perm.iter().map(|r| v[*r]).collect();
+            u.solve(&l.solve(&pv)?)
+        };
+        let mut x = lu_solve(&b)?;
+        let mut d_prev = 1. / 0.;
+        for _ in 0..MAX_ITER {
+            let r = R::residual(&m, &x, &b);
+            let d = lu_solve(&r)?;
+            let d_norm = LInf::vec_norm(&d);
+            if d_norm >= d_prev || d_norm.is_nan() {
+                // the correction is no longer shrinking, we are at the
+                // limit of what the factorization can resolve
+                break;
+            }
+            for (xi, di) in x.iter_mut().zip(d.iter()) {
+                *xi += *di;
+            }
+            if d_norm <= f64::EPSILON * LInf::vec_norm(&x) {
+                break;
+            }
+            d_prev = d_norm;
+        }
+
+        // normwise backward error ||r|| / (||A|| ||x|| + ||b||). a backward
+        // stable solve gets it down to a small multiple of eps, the bound
+        // here only rejects solutions that are plainly wrong
+        let r = R::residual(&m, &x, &b);
+        let scale = LInf::norm(&m) * LInf::vec_norm(&x) + LInf::vec_norm(&b);
+        let err = LInf::vec_norm(&r);
+        if err > f64::EPSILON.sqrt() * scale || err.is_nan() {
+            return Err(());
+        }
+        Ok(x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lu_dec::{Doolittle, PivotedDoolittle};
+    use crate::upper_triangle::{Gaussian, PartialPivot};
+
+    fn needs_swap() -> Mat {
+        let mut m = Mat::new(3, 3);
+        for (i, v) in [0., 1., 2., 1., 0., 3., 4., 5., 6.].iter().enumerate() {
+            m.set(i / 3, i % 3, *v);
+        }
+        m
+    }
+
+    #[test]
+    fn fails_without_pivoting_when_a_swap_is_needed() {
+        let res = IterativeRefinement::<Doolittle<Gaussian>, WorkingPrecision>::solve(
+            needs_swap(),
+            vec![1., 2., 3.],
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn pivoted_factorization() {
+        let m = needs_swap();
+        let b = vec![1., 2., 3.];
+        let x = IterativeRefinement::<PivotedDoolittle<PartialPivot>, ExtendedPrecision>::solve(
+            m.clone(),
+            b.clone(),
+        )
+        .unwrap();
+        assert!(m.residual(&x, &b).norm::<LInf>() < 1e-14);
+    }
+
+    #[test]
+    fn hilbert() {
+        let m = Mat::new_hilbert(8);
+        let x_true = vec![1.0; 8];
+        let b = &m * &x_true;
+        let x = IterativeRefinement::<Doolittle<Gaussian>, ExtendedPrecision>::solve(m, b).unwrap();
+        for xi in x.iter() {
+            assert!((xi - 1.0).abs() < 1e-4);
+        }
+    }
+}
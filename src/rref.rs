@@ -0,0 +1,125 @@
+use crate::mat::{Mat, RowOperation};
+use crate::upper_triangle::find_pivot;
+use crate::vector::Vector;
+
+// reduced row echelon form of an arbitrary m x n matrix,
+// along with the columns that contain its pivots
+#[derive(Debug, Clone)]
+pub struct Rref {
+    mat: Mat,
+    pivots: Vec<usize>,
+}
+
+impl Rref {
+    // reduce a matrix, treating any candidate pivot
+    // with magnitude <= tol as zero
+    pub fn new(m: &Mat, tol: f64) -> Self {
+        Rref::with_ops(m, tol, &mut |_| {})
+    }
+
+    // reduce a matrix, executing the closure every time
+    // a row operation is performed
+    pub fn with_ops<F: FnMut(&RowOperation)>(m: &Mat, tol: f64, h: &mut F) -> Self {
+        let mut mat = m.clone();
+        let mut pivots = vec![];
+        let mut r = 0;
+        for c in 0..mat.cols() {
+            if r == mat.rows() {
+                break;
+            }
+            // partial pivoting, choose the largest magnitude candidate
+            let p = find_pivot(&mat, r, c);
+            if mat.get(p, c).abs() <= tol {
+                // no usable pivot, the remainder of this column is noise
+                for i in r..mat.rows() {
+                    mat.set(i, c, 0.0);
+                }
+                continue;
+            }
+            if p != r {
+                let op = RowOperation::Swap(r, p);
+                mat.apply(&op);
+                h(&op);
+            }
+            let op = RowOperation::Scale {
+                row: r,
+                scale: 1.0 / mat.get(r, c),
+            };
+            mat.apply(&op);
+            h(&op);
+            for i in 0..mat.rows() {
+                if i == r || mat.get(i, c) == 0.0 {
+                    continue;
+                }
+                let op = RowOperation::Cmb {
+                    src: r,
+                    scale: mat.get(i, c),
+                    dest: i,
+                };
+                mat.apply(&op);
+                h(&op);
+            }
+            pivots.push(c);
+            r += 1;
+        }
+        Rref { mat, pivots }
+    }
+
+    // a reasonable default tolerance, based on the size
+    // of the matrix and the magnitude of its entries
+    pub fn default_tol(m: &Mat) -> f64 {
+        (m.rows().max(m.cols()) as f64) * f64::EPSILON * m.max()
+    }
+
+    // the matrix in reduced row echelon form
+    pub fn mat(&self) -> &Mat {
+        &self.mat
+    }
+
+    // the columns containing a pivot
+    pub fn pivots(&self) -> &[usize] {
+        &self.pivots
+    }
+
+    // rank of the original matrix
+    pub fn rank(&self) -> usize {
+        self.pivots.len()
+    }
+
+    // basis for the null space of the original matrix,
+    // one vector for every free column
+    pub fn null_space(&self) -> Vec<Vector> {
+        let n = self.mat.cols();
+        let mut res = vec![];
+        let mut pivots = self.pivots.iter().peekable();
+        for f in 0..n {
+            if pivots.peek() == Some(&&f) {
+                pivots.next();
+                continue;
+            }
+            let mut x = Vector::new_e(n, f);
+            for (r, p) in self.pivots.iter().enumerate() {
+                x[*p] = -self.mat.get(r, f);
+            }
+            res.push(x);
+        }
+        res
+    }
+
+    // basis for the column space of the original matrix m,
+    // made up of the pivot columns of m
+    pub fn column_space(&self, m: &Mat) -> Vec<Vector> {
+        assert_eq!(m.cols(), self.mat.cols());
+        self.pivots
+            .iter()
+            .map(|c| m.iter_col(*c).cloned().collect())
+            .collect()
+    }
+}
+
+impl Mat {
+    // rank of the matrix, using the default tolerance
+    pub fn rank(&self) -> usize {
+        Rref::new(self, Rref::default_tol(self)).rank()
+    }
+}
@@ -0,0 +1,274 @@
+use crate::io::{checked_len, ReadError};
+use crate::mat::Mat;
+use crate::triangular::{LowerTriangular, UpperTriangular};
+use std::convert::TryFrom;
+use std::io::{self, Read, Write};
+
+// every binary file starts with these bytes, followed by a kind byte
+// ('D' dense, 'L' lower or 'U' upper triangular), a unit diagonal flag
+// and the number of rows and columns
+const MAGIC: &[u8; 4] = b"M166";
+
+// trait representing a matrix that can be cached in a compact binary
+// format: a small header followed by the stored values as little endian
+// f64, row by row. triangular matrices only store their packed triangle
+pub trait Binary: Sized {
+    fn write_binary<W: Write>(&self, w: W) -> io::Result<()>;
+    fn read_binary<R: Read>(r: R) -> Result<Self, ReadError>;
+}
+
+fn write_header<W: Write>(
+    mut w: W,
+    kind: u8,
+    unit: bool,
+    rows: usize,
+    cols: usize,
+) -> io::Result<()> {
+    w.write_all(MAGIC)?;
+    w.write_all(&[kind, unit as u8])?;
+    w.write_all(&(rows as u64).to_le_bytes())?;
+    w.write_all(&(cols as u64).to_le_bytes())
+}
+
+// read the header, checking the kind, and return the shape and unit flag
+fn read_header<R: Read>(mut r: R, kind: u8) -> Result<(usize, usize, bool), ReadError> {
+    let mut magic = [0; 4];
+    r.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(ReadError::Format("not a binary matrix file".to_string()));
+    }
+    let mut flags = [0; 2];
+    r.read_exact(&mut flags)?;
+    if flags[0] != kind {
+        return Err(ReadError::Format(format!(
+            "expected a matrix of kind {:?}, found {:?}",
+            kind as char, flags[0] as char
+        )));
+    }
+    let rows = read_u64(&mut r)?;
+    let cols = read_u64(&mut r)?;
+    if rows == 0 || cols == 0 {
+        return Err(ReadError::Empty);
+    }
+    // the shape comes straight from the file, check it before allocating
+    let too_large = || ReadError::Format(format!("a {} x {} matrix is too large", rows, cols));
+    let rows = usize::try_from(rows).map_err(|_| too_large())?;
+    let cols = usize::try_from(cols).map_err(|_| too_large())?;
+    checked_len(rows, cols).ok_or_else(too_large)?;
+    Ok((rows, cols, flags[1] != 0))
+}
+
+fn read_u64<R: Read>(mut r: R) -> io::Result<u64> {
+    let mut buf = [0; 8];
+    r.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+fn read_f64<R: Read>(mut r: R) -> io::Result<f64> {
+    let mut buf = [0; 8];
+    r.read_exact(&mut buf)?;
+    Ok(f64::from_le_bytes(buf))
+}
+
+impl Binary for Mat {
+    fn write_binary<W: Write>(&self, mut w: W) -> io::Result<()> {
+        write_header(&mut w, b'D', false, self.rows(), self.cols())?;
+        for row in self.iter() {
+            for v in row.iter() {
+                w.write_all(&v.to_le_bytes())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn read_binary<R: Read>(mut r: R) -> Result<Self, ReadError> {
+        let (rows, cols, _) = read_header(&mut r, b'D')?;
+        let mut res = Mat::new(rows, cols);
+        for row in 0..rows {
+            for col in 0..cols {
+                res.set(row, col, read_f64(&mut r)?);
+            }
+        }
+        Ok(res)
+    }
+}
+
+// write the packed lower triangle row by row, skipping a unit diagonal
+fn write_lower<W: Write>(l: &LowerTriangular, mut w: W, kind: u8) -> io::Result<()> {
+    let n = l.size();
+    write_header(&mut w, kind, l.is_unit(), n, n)?;
+    for r in 0..n {
+        for c in 0..=r {
+            if !(l.is_unit() && r == c) {
+                w.write_all(&l.get(r, c).to_le_bytes())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn read_lower<R: Read>(mut r: R, kind: u8) -> Result<LowerTriangular, ReadError> {
+    let (n, cols, unit) = read_header(&mut r, kind)?;
+    if n != cols {
+        return Err(ReadError::Format(
+            "triangular matrices must be square".to_string(),
+        ));
+    }
+    let mut res = if unit {
+        LowerTriangular::new_unit(n)
+    } else {
+        LowerTriangular::new(n)
+    };
+    for row in 0..n {
+        for col in 0..=row {
+            if !(unit && row == col) {
+                res.set(row, col, read_f64(&mut r)?);
+            }
+        }
+    }
+    Ok(res)
+}
+
+impl Binary for LowerTriangular {
+    fn write_binary<W: Write>(&self, w: W) -> io::Result<()> {
+        write_lower(self, w, b'L')
+    }
+
+    fn read_binary<R: Read>(r: R) -> Result<Self, ReadError> {
+        read_lower(r, b'L')
+    }
+}
+
+// stored as its transpose, which has the same packed layout
+impl Binary for UpperTriangular {
+    fn write_binary<W: Write>(&self, w: W) -> io::Result<()> {
+        write_lower(&self.transpose(), w, b'U')
+    }
+
+    fn read_binary<R: Read>(r: R) -> Result<Self, ReadError> {
+        read_lower(r, b'U').map(|l| l.transpose())
+    }
+}
+
+// with the serde feature, a matrix is serialized as its shape and
+// its values row by row, e.g. {"rows": 2, "cols": 2, "data": [1, 0, 0, 1]}
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use crate::mat::Mat;
+    use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+    use serde::ser::{Serialize, SerializeStruct, Serializer};
+    use std::fmt;
+
+    const FIELDS: &[&str] = &["rows", "cols", "data"];
+
+    impl Serialize for Mat {
+        fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+            let data: Vec<f64> = self.iter().flat_map(|row| row.iter().cloned()).collect();
+            let mut st = s.serialize_struct("Mat", 3)?;
+            st.serialize_field("rows", &self.rows())?;
+            st.serialize_field("cols", &self.cols())?;
+            st.serialize_field("data", &data)?;
+            st.end()
+        }
+    }
+
+    // check the shape against the data before constructing the matrix
+    fn build<E: de::Error>(rows: usize, cols: usize, data: Vec<f64>) -> Result<Mat, E> {
+        if rows == 0 || cols == 0 {
+            return Err(E::custom("matrix must have at least one row and column"));
+        }
+        if rows.checked_mul(cols) != Some(data.len()) {
+            return Err(E::custom(format!(
+                "expected {} x {} values, found {}",
+                rows,
+                cols,
+                data.len()
+            )));
+        }
+        let mut res = Mat::new(rows, cols);
+        for (i, v) in data.into_iter().enumerate() {
+            res.set(i / cols, i % cols, v);
+        }
+        Ok(res)
+    }
+
+    struct MatVisitor;
+
+    impl<'de> Visitor<'de> for MatVisitor {
+        type Value = Mat;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "a matrix with rows, cols and data")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Mat, A::Error> {
+            let rows = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+            let cols = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+            let data = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(2, &self))?;
+            build(rows, cols, data)
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Mat, A::Error> {
+            let mut rows = None;
+            let mut cols = None;
+            let mut data = None;
+            while let Some(key) = map.next_key::<String>()? {
+                match key.as_str() {
+                    "rows" => rows = Some(map.next_value()?),
+                    "cols" => cols = Some(map.next_value()?),
+                    "data" => data = Some(map.next_value()?),
+                    k => return Err(de::Error::unknown_field(k, FIELDS)),
+                }
+            }
+            build(
+                rows.ok_or_else(|| de::Error::missing_field("rows"))?,
+                cols.ok_or_else(|| de::Error::missing_field("cols"))?,
+                data.ok_or_else(|| de::Error::missing_field("data"))?,
+            )
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Mat {
+        fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+            d.deserialize_struct("Mat", FIELDS, MatVisitor)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(rows: u64, cols: u64) -> Vec<u8> {
+        let mut res = MAGIC.to_vec();
+        res.extend_from_slice(&[b'D', 0]);
+        res.extend_from_slice(&rows.to_le_bytes());
+        res.extend_from_slice(&cols.to_le_bytes());
+        res
+    }
+
+    #[test]
+    fn huge_shapes_are_a_format_error() {
+        for (rows, cols) in [(u64::MAX, 2), (1 << 32, 1 << 32), (1 << 20, 1 << 20)].iter() {
+            let res = Mat::read_binary(&header(*rows, *cols)[..]);
+            assert!(matches!(res, Err(ReadError::Format(_))));
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut m = Mat::new(2, 3);
+        m.set(0, 1, 1.5);
+        m.set(1, 2, -4.0);
+        let mut buf = vec![];
+        m.write_binary(&mut buf).unwrap();
+        let res = Mat::read_binary(&buf[..]).unwrap();
+        assert!(res.iter().eq(m.iter()));
+    }
+}
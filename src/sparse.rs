@@ -0,0 +1,346 @@
+use crate::linear_operator::LinearOperator;
+use crate::mat::Mat;
+use crate::vector::Vector;
+use std::ops::Mul;
+
+// sort (major, minor, value) triplets, sum duplicates and drop zeros,
+// then compress them into pointer / index / value arrays
+fn compress(
+    n_major: usize,
+    n_minor: usize,
+    mut entries: Vec<(usize, usize, f64)>,
+) -> (Vec<usize>, Vec<usize>, Vec<f64>) {
+    entries.sort_by(|(a1, b1, _), (a2, b2, _)| (a1, b1).cmp(&(a2, b2)));
+    let mut ptr = vec![0; n_major + 1];
+    let mut idx: Vec<usize> = vec![];
+    let mut vals: Vec<f64> = vec![];
+    let mut last = None;
+    for (major, minor, v) in entries {
+        assert!(major < n_major);
+        assert!(minor < n_minor);
+        if last == Some((major, minor)) {
+            *vals.last_mut().unwrap() += v;
+        } else {
+            ptr[major + 1] += 1;
+            idx.push(minor);
+            vals.push(v);
+            last = Some((major, minor));
+        }
+    }
+    for i in 0..n_major {
+        ptr[i + 1] += ptr[i];
+    }
+
+    // summing duplicates may have produced explicit zeros, remove them
+    if vals.contains(&0.0) {
+        let mut new_ptr = vec![0; n_major + 1];
+        let mut new_idx = vec![];
+        let mut new_vals = vec![];
+        for i in 0..n_major {
+            for k in ptr[i]..ptr[i + 1] {
+                if vals[k] != 0.0 {
+                    new_idx.push(idx[k]);
+                    new_vals.push(vals[k]);
+                }
+            }
+            new_ptr[i + 1] = new_idx.len();
+        }
+        return (new_ptr, new_idx, new_vals);
+    }
+    (ptr, idx, vals)
+}
+
+#[derive(Debug, Clone)]
+// compressed sparse row matrix. the non-zero entries of row r are
+// vals[row_ptr[r]..row_ptr[r + 1]], in the columns given by col_idx
+pub struct Csr {
+    rows: usize,
+    cols: usize,
+    row_ptr: Vec<usize>,
+    col_idx: Vec<usize>,
+    vals: Vec<f64>,
+}
+
+impl Csr {
+    // construct a new matrix from (row, col, value) triplets,
+    // summing duplicate entries
+    pub fn from_triplets(rows: usize, cols: usize, triplets: &[(usize, usize, f64)]) -> Self {
+        assert!(rows > 0);
+        assert!(cols > 0);
+        let (row_ptr, col_idx, vals) = compress(rows, cols, triplets.to_vec());
+        Csr {
+            rows,
+            cols,
+            row_ptr,
+            col_idx,
+            vals,
+        }
+    }
+
+    // construct a new identity matrix
+    pub fn new_i(size: usize) -> Self {
+        let triplets: Vec<_> = (0..size).map(|i| (i, i, 1.0)).collect();
+        Csr::from_triplets(size, size, &triplets)
+    }
+
+    // get a value from the matrix
+    // complexity O(log k), where k is the number of non-zeros in the row
+    pub fn get(&self, row: usize, col: usize) -> f64 {
+        let range = self.row_ptr[row]..self.row_ptr[row + 1];
+        match self.col_idx[range.clone()].binary_search(&col) {
+            Ok(i) => self.vals[range.start + i],
+            Err(_) => 0.0,
+        }
+    }
+
+    // number of stored non-zero entries
+    pub fn nnz(&self) -> usize {
+        self.vals.len()
+    }
+
+    // iterate through the (column, value) non-zero entries of a row
+    pub fn iter_row(&self, row: usize) -> impl Iterator<Item = (usize, f64)> + '_ {
+        let range = self.row_ptr[row]..self.row_ptr[row + 1];
+        self.col_idx[range.clone()]
+            .iter()
+            .cloned()
+            .zip(self.vals[range].iter().cloned())
+    }
+
+    // iterate through the (row, column, value) non-zero entries
+    pub fn triplets(&self) -> impl Iterator<Item = (usize, usize, f64)> + '_ {
+        (0..self.rows).flat_map(move |r| self.iter_row(r).map(move |(c, v)| (r, c, v)))
+    }
+
+    // number of rows in the matrix
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    // number of columns in the matrix
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    // convert to compressed sparse column storage
+    pub fn to_csc(&self) -> Csc {
+        let triplets: Vec<_> = self.triplets().collect();
+        Csc::from_triplets(self.rows, self.cols, &triplets)
+    }
+
+    // construct the transpose of the matrix
+    pub fn transpose(&self) -> Self {
+        let triplets: Vec<_> = self.triplets().map(|(r, c, v)| (c, r, v)).collect();
+        Csr::from_triplets(self.cols, self.rows, &triplets)
+    }
+}
+
+#[derive(Debug, Clone)]
+// compressed sparse column matrix. the non-zero entries of column c are
+// vals[col_ptr[c]..col_ptr[c + 1]], in the rows given by row_idx
+pub struct Csc {
+    rows: usize,
+    cols: usize,
+    col_ptr: Vec<usize>,
+    row_idx: Vec<usize>,
+    vals: Vec<f64>,
+}
+
+impl Csc {
+    // construct a new matrix from (row, col, value) triplets,
+    // summing duplicate entries
+    pub fn from_triplets(rows: usize, cols: usize, triplets: &[(usize, usize, f64)]) -> Self {
+        assert!(rows > 0);
+        assert!(cols > 0);
+        let entries = triplets.iter().map(|(r, c, v)| (*c, *r, *v)).collect();
+        let (col_ptr, row_idx, vals) = compress(cols, rows, entries);
+        Csc {
+            rows,
+            cols,
+            col_ptr,
+            row_idx,
+            vals,
+        }
+    }
+
+    // get a value from the matrix
+    // complexity O(log k), where k is the number of non-zeros in the column
+    pub fn get(&self, row: usize, col: usize) -> f64 {
+        let range = self.col_ptr[col]..self.col_ptr[col + 1];
+        match self.row_idx[range.clone()].binary_search(&row) {
+            Ok(i) => self.vals[range.start + i],
+            Err(_) => 0.0,
+        }
+    }
+
+    // number of stored non-zero entries
+    pub fn nnz(&self) -> usize {
+        self.vals.len()
+    }
+
+    // iterate through the (row, value) non-zero entries of a column
+    pub fn iter_col(&self, col: usize) -> impl Iterator<Item = (usize, f64)> + '_ {
+        let range = self.col_ptr[col]..self.col_ptr[col + 1];
+        self.row_idx[range.clone()]
+            .iter()
+            .cloned()
+            .zip(self.vals[range].iter().cloned())
+    }
+
+    // iterate through the (row, column, value) non-zero entries
+    pub fn triplets(&self) -> impl Iterator<Item = (usize, usize, f64)> + '_ {
+        (0..self.cols).flat_map(move |c| self.iter_col(c).map(move |(r, v)| (r, c, v)))
+    }
+
+    // number of rows in the matrix
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    // number of columns in the matrix
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    // convert to compressed sparse row storage
+    pub fn to_csr(&self) -> Csr {
+        let triplets: Vec<_> = self.triplets().collect();
+        Csr::from_triplets(self.rows, self.cols, &triplets)
+    }
+}
+
+impl LinearOperator for Csr {
+    fn rows(&self) -> usize {
+        self.rows
+    }
+
+    fn cols(&self) -> usize {
+        self.cols
+    }
+
+    fn mul_vec(&self, x: &[f64]) -> Vector {
+        assert_eq!(self.cols, x.len());
+        (0..self.rows).map(|r| self.row_dot(r, x)).collect()
+    }
+
+    fn row_dot(&self, r: usize, x: &[f64]) -> f64 {
+        self.iter_row(r).map(|(c, v)| v * x[c]).sum()
+    }
+
+    fn diag(&self, i: usize) -> f64 {
+        self.get(i, i)
+    }
+}
+
+impl LinearOperator for Csc {
+    fn rows(&self) -> usize {
+        self.rows
+    }
+
+    fn cols(&self) -> usize {
+        self.cols
+    }
+
+    fn mul_vec(&self, x: &[f64]) -> Vector {
+        assert_eq!(self.cols, x.len());
+        let mut res = Vector::new(self.rows);
+        for (c, xc) in x.iter().enumerate() {
+            for (r, v) in self.iter_col(c) {
+                res[r] += v * xc;
+            }
+        }
+        res
+    }
+
+    // WARNING: complexity O(n log k), CSC storage is not suited to row access
+    fn row_dot(&self, r: usize, x: &[f64]) -> f64 {
+        x.iter()
+            .enumerate()
+            .map(|(c, xc)| self.get(r, c) * xc)
+            .sum()
+    }
+
+    fn diag(&self, i: usize) -> f64 {
+        self.get(i, i)
+    }
+}
+
+impl From<&Mat> for Csr {
+    fn from(m: &Mat) -> Self {
+        let triplets: Vec<_> = mat_triplets(m).collect();
+        Csr::from_triplets(m.rows(), m.cols(), &triplets)
+    }
+}
+
+impl From<&Mat> for Csc {
+    fn from(m: &Mat) -> Self {
+        let triplets: Vec<_> = mat_triplets(m).collect();
+        Csc::from_triplets(m.rows(), m.cols(), &triplets)
+    }
+}
+
+impl From<&Csr> for Mat {
+    fn from(s: &Csr) -> Self {
+        let mut res = Mat::new(s.rows, s.cols);
+        for (r, c, v) in s.triplets() {
+            res.set(r, c, v);
+        }
+        res
+    }
+}
+
+impl From<&Csc> for Mat {
+    fn from(s: &Csc) -> Self {
+        let mut res = Mat::new(s.rows, s.cols);
+        for (r, c, v) in s.triplets() {
+            res.set(r, c, v);
+        }
+        res
+    }
+}
+
+// non-zero entries of a dense matrix
+fn mat_triplets(m: &Mat) -> impl Iterator<Item = (usize, usize, f64)> + '_ {
+    m.iter().enumerate().flat_map(|(r, row)| {
+        row.iter()
+            .enumerate()
+            .filter(|(_, v)| **v != 0.0)
+            .map(move |(c, v)| (r, c, *v))
+    })
+}
+
+// sparse matrix * vector
+impl Mul<&Vec<f64>> for &Csr {
+    type Output = Vec<f64>;
+
+    fn mul(self, rhs: &Vec<f64>) -> Self::Output {
+        self.mul_vec(rhs).into_vec()
+    }
+}
+
+// sparse matrix * vector
+impl Mul<&Vec<f64>> for &Csc {
+    type Output = Vec<f64>;
+
+    fn mul(self, rhs: &Vec<f64>) -> Self::Output {
+        self.mul_vec(rhs).into_vec()
+    }
+}
+
+// sparse matrix * vector
+impl Mul<&Vector> for &Csr {
+    type Output = Vector;
+
+    fn mul(self, rhs: &Vector) -> Self::Output {
+        self.mul_vec(rhs)
+    }
+}
+
+// sparse matrix * vector
+impl Mul<&Vector> for &Csc {
+    type Output = Vector;
+
+    fn mul(self, rhs: &Vector) -> Self::Output {
+        self.mul_vec(rhs)
+    }
+}
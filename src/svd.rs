@@ -0,0 +1,255 @@
+use crate::mat::Mat;
+use crate::norm::{VecNorm, L2};
+
+// trait representing a method of (thin) singular value decomposition.
+// for an m x n matrix with k = min(m, n), returns U (m x k) with
+// orthonormal columns, the k singular values in descending order,
+// and V^T (k x n) with orthonormal rows, such that A = U diag(s) V^T
+pub trait SvdDec {
+    fn dec(m: Mat) -> Result<(Mat, Vec<f64>, Mat), ()>;
+}
+
+// one-sided jacobi (hestenes) method. rotates pairs of columns of A until
+// they are all mutually orthogonal, accumulating the rotations in V.
+// the column norms are then the singular values
+pub struct OneSidedJacobi;
+
+impl SvdDec for OneSidedJacobi {
+    fn dec(m: Mat) -> Result<(Mat, Vec<f64>, Mat), ()> {
+        const MAX_SWEEPS: usize = 100;
+
+        if m.iter().any(|row| row.iter().any(|v| !v.is_finite())) {
+            return Err(());
+        }
+        if m.rows() < m.cols() {
+            // A^T = U S V^T  =>  A = V S U^T
+            let (u, s, vt) = Self::dec(m.transpose())?;
+            return Ok((vt.transpose(), s, u.transpose()));
+        }
+
+        let rows = m.rows();
+        let cols = m.cols();
+        // work on columns, they are what gets rotated
+        let mut a: Vec<Vec<f64>> = (0..cols)
+            .map(|c| m.iter_col(c).cloned().collect())
+            .collect();
+        let mut v: Vec<Vec<f64>> = (0..cols)
+            .map(|c| (0..cols).map(|r| if r == c { 1. } else { 0. }).collect())
+            .collect();
+        // squared frobenius norm, which the rotations preserve
+        let frob2: f64 = a.iter().map(|c| dot(c, c)).sum();
+
+        let mut sweep = 0;
+        loop {
+            if sweep >= MAX_SWEEPS {
+                return Err(());
+            }
+            let mut rotated = false;
+            for p in 0..cols {
+                for q in (p + 1)..cols {
+                    let alpha = dot(&a[p], &a[p]);
+                    let beta = dot(&a[q], &a[q]);
+                    let gamma = dot(&a[p], &a[q]);
+                    if gamma == 0.0 || f64::abs(gamma) <= f64::EPSILON * f64::sqrt(alpha * beta) {
+                        continue;
+                    }
+                    // a numerically zero column never passes the relative test
+                    // above, it is orthogonal to everything up to rounding
+                    if alpha.min(beta) <= f64::EPSILON * f64::EPSILON * frob2 {
+                        continue;
+                    }
+                    rotated = true;
+                    let zeta = (beta - alpha) / (2. * gamma);
+                    let t = zeta.signum() / (f64::abs(zeta) + f64::sqrt(1. + zeta * zeta));
+                    let c = 1. / f64::sqrt(1. + t * t);
+                    let s = c * t;
+                    rotate(&mut a, p, q, c, s);
+                    rotate(&mut v, p, q, c, s);
+                }
+            }
+            if !rotated {
+                break;
+            }
+            sweep += 1;
+        }
+
+        let mut sigma: Vec<f64> = a.iter().map(|c| L2::vec_norm(c)).collect();
+        if sigma.iter().any(|s| !s.is_finite()) {
+            // the values overflowed
+            return Err(());
+        }
+        let mut order: Vec<usize> = (0..cols).collect();
+        order.sort_by(|i, j| sigma[*j].total_cmp(&sigma[*i]));
+
+        // normalize the columns of A to get U, completing the basis
+        // with standard basis vectors wherever a singular value is zero.
+        // a column skipped as numerically zero above is only rounding
+        // error, normalizing it would not give an orthogonal column
+        let zero = f64::EPSILON * f64::sqrt(frob2);
+        let mut u_cols: Vec<Vec<f64>> = vec![];
+        for i in order.iter() {
+            let mut col = a[*i].clone();
+            if sigma[*i] > zero {
+                for x in col.iter_mut() {
+                    *x /= sigma[*i];
+                }
+            } else {
+                sigma[*i] = 0.0;
+                col = (0..rows)
+                    .map(|e| {
+                        let mut x: Vec<f64> =
+                            (0..rows).map(|r| if r == e { 1. } else { 0. }).collect();
+                        for u in u_cols.iter() {
+                            let d = dot(u, &x);
+                            for (xi, ui) in x.iter_mut().zip(u.iter()) {
+                                *xi -= d * *ui;
+                            }
+                        }
+                        x
+                    })
+                    .max_by(|x, y| L2::vec_norm(x).total_cmp(&L2::vec_norm(y)))
+                    .unwrap();
+                let n = L2::vec_norm(&col);
+                for x in col.iter_mut() {
+                    *x /= n;
+                }
+            }
+            u_cols.push(col);
+        }
+
+        let mut u = Mat::new(rows, cols);
+        let mut vt = Mat::new(cols, cols);
+        let mut s = Vec::with_capacity(cols);
+        for (k, i) in order.iter().enumerate() {
+            for (r, x) in u_cols[k].iter().enumerate() {
+                u.set(r, k, *x);
+            }
+            for (c, x) in v[*i].iter().enumerate() {
+                vt.set(k, c, *x);
+            }
+            s.push(sigma[*i]);
+        }
+        Ok((u, s, vt))
+    }
+}
+
+fn dot(x: &[f64], y: &[f64]) -> f64 {
+    x.iter().zip(y.iter()).map(|(a, b)| *a * *b).sum()
+}
+
+// rotate columns p and q by the given cosine and sine
+fn rotate(cols: &mut [Vec<f64>], p: usize, q: usize, c: f64, s: f64) {
+    for k in 0..cols[p].len() {
+        let x = cols[p][k];
+        let y = cols[q][k];
+        cols[p][k] = c * x - s * y;
+        cols[q][k] = s * x + c * y;
+    }
+}
+
+impl Mat {
+    // calculate the 2-norm of the matrix, its largest singular value
+    pub fn norm2<S: SvdDec>(&self) -> Result<f64, ()> {
+        let (_, s, _) = S::dec(self.clone())?;
+        Ok(s[0])
+    }
+
+    // calculate the 2-norm condition number sigma_max / sigma_min,
+    // infinite for a singular matrix
+    pub fn cond2<S: SvdDec>(&self) -> Result<f64, ()> {
+        let (_, s, _) = S::dec(self.clone())?;
+        let s_min = s[s.len() - 1];
+        if s_min == 0.0 {
+            return Ok(f64::INFINITY);
+        }
+        Ok(s[0] / s_min)
+    }
+
+    // best rank k approximation of the matrix in the 2-norm,
+    // keeping only the k largest singular values
+    pub fn low_rank<S: SvdDec>(&self, k: usize) -> Result<Mat, ()> {
+        let (u, s, vt) = S::dec(self.clone())?;
+        let mut res = Mat::new(self.rows(), self.cols());
+        for (i, sigma) in s.iter().enumerate().take(k) {
+            for r in 0..res.rows() {
+                for c in 0..res.cols() {
+                    res.set(r, c, res.get(r, c) + sigma * u.get(r, i) * vt.get(i, c));
+                }
+            }
+        }
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mat(rows: usize, cols: usize, values: &[f64]) -> Mat {
+        let mut m = Mat::new(rows, cols);
+        for (i, v) in values.iter().enumerate() {
+            m.set(i / cols, i % cols, *v);
+        }
+        m
+    }
+
+    fn assert_close(a: &Mat, b: &Mat) {
+        assert_eq!((a.rows(), a.cols()), (b.rows(), b.cols()));
+        for (ra, rb) in a.iter().zip(b.iter()) {
+            for (x, y) in ra.iter().zip(rb.iter()) {
+                assert!((x - y).abs() < 1e-12, "\n{}\n!=\n{}", a, b);
+            }
+        }
+    }
+
+    // check A = U diag(s) V^T with orthonormal U and V and sorted s
+    fn check_svd(m: &Mat) {
+        let (u, s, vt) = OneSidedJacobi::dec(m.clone()).unwrap();
+        let k = m.rows().min(m.cols());
+        assert_eq!((u.rows(), u.cols()), (m.rows(), k));
+        assert_eq!((vt.rows(), vt.cols()), (k, m.cols()));
+        assert_eq!(s.len(), k);
+        assert!(s.windows(2).all(|w| w[0] >= w[1] && w[1] >= 0.0));
+
+        let mut us = u.clone();
+        for r in 0..us.rows() {
+            for (c, sigma) in s.iter().enumerate() {
+                us.set(r, c, u.get(r, c) * sigma);
+            }
+        }
+        assert_close(&(&us * &vt), m);
+        assert_close(&(&u.transpose() * &u), &Mat::new_i(k));
+        assert_close(&(&vt * &vt.transpose()), &Mat::new_i(k));
+    }
+
+    #[test]
+    fn square_tall_and_wide() {
+        check_svd(&mat(2, 2, &[3., 1., 1., 3.]));
+        check_svd(&mat(3, 2, &[1., 2., 3., 4., 5., 6.]));
+        check_svd(&mat(2, 3, &[1., 2., 3., 4., 5., 6.]));
+    }
+
+    #[test]
+    fn rank_deficient() {
+        check_svd(&mat(3, 3, &[1., 2., 3., 2., 4., 6., 1., 0., 1.]));
+        check_svd(&mat(3, 3, &[2., 1., 3., 4., 2., 6., 0., 1., 1.]));
+        check_svd(&mat(3, 2, &[1., 3., 2., 6., 3., 9.]));
+        check_svd(&mat(2, 3, &[1., 2., 3., 2., 4., 6.]));
+        check_svd(&Mat::new(3, 3));
+    }
+
+    #[test]
+    fn cond2() {
+        assert_eq!(Mat::new(2, 2).cond2::<OneSidedJacobi>(), Ok(f64::INFINITY));
+        let m = mat(2, 2, &[2., 0., 0., 0.5]);
+        assert!((m.cond2::<OneSidedJacobi>().unwrap() - 4.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn non_finite_is_an_error() {
+        let m = mat(2, 2, &[f64::INFINITY, 1., 1., 1.]);
+        assert!(OneSidedJacobi::dec(m.clone()).is_err());
+        assert!(m.cond2::<OneSidedJacobi>().is_err());
+        assert!(OneSidedJacobi::dec(mat(2, 2, &[f64::NAN, 1., 1., 1.])).is_err());
+    }
+}
@@ -0,0 +1,210 @@
+use crate::eigenvalue::{closest_to, EigenSolve};
+use crate::linear_operator::LinearOperator;
+use crate::lu_dec::cholesky;
+use crate::mat::Mat;
+use crate::triangular::LowerTriangular;
+use crate::vector::Vector;
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::Mul;
+
+// index of (row, col) in the packed upper triangle, where row <= col
+fn packed_index(row: usize, col: usize) -> usize {
+    col * (col + 1) / 2 + row
+}
+
+#[derive(Debug, Clone)]
+// symmetric matrix, storing only the n * (n + 1) / 2 values
+// on or above the diagonal, packed column by column
+pub struct SymMat {
+    n: usize,
+    data: Box<[f64]>,
+}
+
+impl SymMat {
+    // construct a new zero symmetric matrix
+    pub fn new(n: usize) -> Self {
+        assert!(n > 0);
+        SymMat {
+            n,
+            data: vec![0.0; n * (n + 1) / 2].into_boxed_slice(),
+        }
+    }
+
+    // construct a new hilbert matrix
+    pub fn new_hilbert(n: usize) -> Self {
+        let mut res = SymMat::new(n);
+        for j in 0..n {
+            for i in 0..=j {
+                res.set(i, j, 1.0 / (i + j + 1) as f64);
+            }
+        }
+        res
+    }
+
+    // construct a symmetric matrix from a dense one,
+    // failing if the dense matrix is not exactly symmetric
+    pub fn from_mat(m: &Mat) -> Result<Self, ()> {
+        if !m.is_square() {
+            return Err(());
+        }
+        let mut res = SymMat::new(m.rows());
+        for j in 0..m.cols() {
+            for i in 0..=j {
+                if m.get(i, j) != m.get(j, i) {
+                    return Err(());
+                }
+                res.set(i, j, m.get(i, j));
+            }
+        }
+        Ok(res)
+    }
+
+    // size of the matrix
+    pub fn size(&self) -> usize {
+        self.n
+    }
+
+    // get a value from the matrix
+    pub fn get(&self, row: usize, col: usize) -> f64 {
+        assert!(row < self.n && col < self.n);
+        if row <= col {
+            self.data[packed_index(row, col)]
+        } else {
+            self.data[packed_index(col, row)]
+        }
+    }
+
+    // set a value in the matrix, along with its mirror across the diagonal
+    pub fn set(&mut self, row: usize, col: usize, val: f64) {
+        assert!(row < self.n && col < self.n);
+        if row <= col {
+            self.data[packed_index(row, col)] = val;
+        } else {
+            self.data[packed_index(col, row)] = val;
+        }
+    }
+
+    // cholesky decomposition A = L * L^T, only reading the upper triangle.
+    // fails if the matrix is not positive definite
+    pub fn cholesky(&self) -> Result<LowerTriangular, ()> {
+        cholesky(self.n, |i, j| self.get(j, i))
+    }
+
+    // calculate every eigenvalue with the cyclic jacobi eigenvalue method,
+    // rotating away each off-diagonal entry in turn until they are all
+    // smaller than accuracy. symmetric matrices always have real eigenvalues,
+    // so this converges for any input. returned in ascending order
+    pub fn eigenvalues(&self, accuracy: f64) -> Result<Vec<f64>, ()> {
+        const MAX_SWEEPS: usize = 100;
+
+        let n = self.n;
+        let mut a = self.clone();
+        for _ in 0..MAX_SWEEPS {
+            let off_diag = (0..n)
+                .flat_map(|j| (0..j).map(move |i| (i, j)))
+                .map(|(i, j)| f64::abs(a.get(i, j)))
+                .fold(0., f64::max);
+            if off_diag < accuracy {
+                let mut res: Vec<f64> = (0..n).map(|i| a.get(i, i)).collect();
+                res.sort_by(|e1, e2| e1.partial_cmp(e2).unwrap_or(Ordering::Equal));
+                return Ok(res);
+            }
+            for q in 1..n {
+                for p in 0..q {
+                    let apq = a.get(p, q);
+                    if apq == 0.0 {
+                        continue;
+                    }
+                    let theta = (a.get(q, q) - a.get(p, p)) / (2. * apq);
+                    let t = theta.signum() / (f64::abs(theta) + f64::sqrt(theta * theta + 1.));
+                    let c = 1. / f64::sqrt(t * t + 1.);
+                    let s = t * c;
+                    for k in (0..n).filter(|k| *k != p && *k != q) {
+                        let akp = a.get(k, p);
+                        let akq = a.get(k, q);
+                        a.set(k, p, c * akp - s * akq);
+                        a.set(k, q, s * akp + c * akq);
+                    }
+                    a.set(p, p, a.get(p, p) - t * apq);
+                    a.set(q, q, a.get(q, q) + t * apq);
+                    a.set(p, q, 0.0);
+                }
+            }
+        }
+        Err(())
+    }
+}
+
+impl LinearOperator for SymMat {
+    fn rows(&self) -> usize {
+        self.n
+    }
+
+    fn cols(&self) -> usize {
+        self.n
+    }
+
+    // each stored value is read once, contributing to two rows
+    fn mul_vec(&self, x: &[f64]) -> Vector {
+        assert_eq!(self.n, x.len());
+        let mut res = Vector::new(self.n);
+        for j in 0..self.n {
+            for i in 0..j {
+                let v = self.data[packed_index(i, j)];
+                res[i] += v * x[j];
+                res[j] += v * x[i];
+            }
+            res[j] += self.data[packed_index(j, j)] * x[j];
+        }
+        res
+    }
+
+    fn row_dot(&self, r: usize, x: &[f64]) -> f64 {
+        (0..self.n).map(|c| self.get(r, c) * x[c]).sum()
+    }
+
+    fn diag(&self, i: usize) -> f64 {
+        self.get(i, i)
+    }
+}
+
+impl From<&SymMat> for Mat {
+    fn from(s: &SymMat) -> Self {
+        let mut res = Mat::new(s.n, s.n);
+        for r in 0..s.n {
+            for c in 0..s.n {
+                res.set(r, c, s.get(r, c));
+            }
+        }
+        res
+    }
+}
+
+// symmetric matrix * vector
+impl Mul<&Vec<f64>> for &SymMat {
+    type Output = Vec<f64>;
+
+    fn mul(self, rhs: &Vec<f64>) -> Self::Output {
+        self.mul_vec(rhs).into_vec()
+    }
+}
+
+// allow symmetric matrices to be printed by println
+impl fmt::Display for SymMat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Mat::from(self).fmt(f)
+    }
+}
+
+// convert the matrix to packed symmetric storage, then use the cyclic
+// jacobi eigenvalue method. eigen_solve returns the eigenvalue closest to q,
+// and fails if the matrix is not symmetric
+pub struct SymJacobi;
+
+impl EigenSolve for SymJacobi {
+    fn eigen_solve(mat: &Mat, q: f64, accuracy: f64) -> Result<f64, ()> {
+        let es = SymMat::from_mat(mat)?.eigenvalues(accuracy)?;
+        closest_to(&es, q)
+    }
+}
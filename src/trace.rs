@@ -0,0 +1,144 @@
+use crate::format::NumFormat;
+use crate::mat::{Mat, RowOperation};
+use std::fmt;
+use std::marker::PhantomData;
+
+// trait representing a way to render the steps of an elimination
+pub trait TraceFormat {
+    // render a row operation in standard notation
+    fn op(op: &RowOperation) -> String;
+
+    // render the state of the matrix
+    fn mat(m: &Mat) -> String;
+
+    // render one step, the operation performed and the resulting matrix
+    fn step(op: &RowOperation, m: &Mat) -> String {
+        format!("{}\n{}", Self::op(op), Self::mat(m))
+    }
+}
+
+// significant digits shown for coefficients and matrix entries
+const DIGITS: usize = 6;
+
+// plain text, e.g. "R2 ← R2 − 0.5·R1" followed by the matrix
+pub struct PlainText;
+
+impl TraceFormat for PlainText {
+    fn op(op: &RowOperation) -> String {
+        // rows are numbered from 1, as they are on the whiteboard
+        match *op {
+            RowOperation::Swap(r1, r2) => format!("R{} ↔ R{}", r1 + 1, r2 + 1),
+            RowOperation::Cmb { src, scale, dest } => {
+                let sign = if scale < 0.0 { "+" } else { "−" };
+                format!(
+                    "R{} ← R{} {} {}·R{}",
+                    dest + 1,
+                    dest + 1,
+                    sign,
+                    NumFormat::significant(DIGITS).format(scale.abs()),
+                    src + 1
+                )
+            }
+            RowOperation::Scale { row, scale } => {
+                format!(
+                    "R{} ← {}·R{}",
+                    row + 1,
+                    NumFormat::significant(DIGITS).format(scale),
+                    row + 1
+                )
+            }
+        }
+    }
+
+    fn mat(m: &Mat) -> String {
+        m.to_string()
+    }
+}
+
+// LaTeX, e.g. "\xrightarrow{R_{2} \leftarrow R_{2} - 0.5 R_{1}}" followed
+// by a bmatrix, so consecutive steps read as a chain of equivalent matrices
+pub struct Latex;
+
+impl TraceFormat for Latex {
+    fn op(op: &RowOperation) -> String {
+        let body = match *op {
+            RowOperation::Swap(r1, r2) => {
+                format!("R_{{{}}} \\leftrightarrow R_{{{}}}", r1 + 1, r2 + 1)
+            }
+            RowOperation::Cmb { src, scale, dest } => {
+                let sign = if scale < 0.0 { "+" } else { "-" };
+                format!(
+                    "R_{{{}}} \\leftarrow R_{{{}}} {} {} R_{{{}}}",
+                    dest + 1,
+                    dest + 1,
+                    sign,
+                    NumFormat::significant(DIGITS).format_latex(scale.abs()),
+                    src + 1
+                )
+            }
+            RowOperation::Scale { row, scale } => format!(
+                "R_{{{}}} \\leftarrow {} R_{{{}}}",
+                row + 1,
+                NumFormat::significant(DIGITS).format_latex(scale),
+                row + 1
+            ),
+        };
+        format!("\\xrightarrow{{{}}}", body)
+    }
+
+    fn mat(m: &Mat) -> String {
+        m.to_latex(NumFormat::significant(DIGITS))
+    }
+}
+
+// records every step of an elimination in format F. keeps its own copy
+// of the matrix, performing each row operation on it as it is recorded,
+// so the rendered state always matches the matrix being reduced
+pub struct Tracer<F: TraceFormat> {
+    m: Mat,
+    steps: Vec<String>,
+    f: PhantomData<*const F>,
+}
+
+impl<F: TraceFormat> Tracer<F> {
+    // construct a new tracer, starting from the given matrix
+    pub fn new(m: &Mat) -> Self {
+        Tracer {
+            m: m.clone(),
+            steps: vec![F::mat(m)],
+            f: PhantomData,
+        }
+    }
+
+    // perform a row operation and render the step
+    pub fn record(&mut self, op: &RowOperation) {
+        self.m.apply(op);
+        self.steps.push(F::step(op, &self.m));
+    }
+
+    // closure that records every row operation it is called with,
+    // for passing to UpperTriangle::run and ReduceUpper::run
+    pub fn recorder(&mut self) -> impl FnMut(&RowOperation) + '_ {
+        move |op| self.record(op)
+    }
+
+    // the rendered steps, starting with the initial matrix
+    pub fn steps(&self) -> &[String] {
+        &self.steps
+    }
+
+    // the current state of the matrix
+    pub fn mat(&self) -> &Mat {
+        &self.m
+    }
+}
+
+// print every step, one after the other
+impl<F: TraceFormat> fmt::Display for Tracer<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for step in self.steps.iter() {
+            writeln!(f, "{}", step)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,457 @@
+use crate::mat::Mat;
+use std::fmt;
+use std::ops::Mul;
+
+// number of values stored for an n x n triangle
+fn packed_len(n: usize) -> usize {
+    n * (n + 1) / 2
+}
+
+// index of (major, minor) in a packed triangle, where minor <= major
+fn packed_index(major: usize, minor: usize) -> usize {
+    major * (major + 1) / 2 + minor
+}
+
+#[derive(Debug, Clone)]
+// lower triangular matrix, packed row by row so only
+// the n * (n + 1) / 2 values on or below the diagonal are stored.
+// unit lower triangular matrices have a diagonal fixed at 1
+pub struct LowerTriangular {
+    n: usize,
+    unit: bool,
+    data: Box<[f64]>,
+}
+
+impl LowerTriangular {
+    // construct a new zero lower triangular matrix
+    pub fn new(n: usize) -> Self {
+        assert!(n > 0);
+        LowerTriangular {
+            n,
+            unit: false,
+            data: vec![0.0; packed_len(n)].into_boxed_slice(),
+        }
+    }
+
+    // construct a new unit lower triangular matrix, with only 1 on the diagonal
+    pub fn new_unit(n: usize) -> Self {
+        let mut res = LowerTriangular::new(n);
+        for i in 0..n {
+            res.data[packed_index(i, i)] = 1.0;
+        }
+        res.unit = true;
+        res
+    }
+
+    // size of the matrix
+    pub fn size(&self) -> usize {
+        self.n
+    }
+
+    // is the diagonal fixed at 1?
+    pub fn is_unit(&self) -> bool {
+        self.unit
+    }
+
+    // get a value from the matrix
+    pub fn get(&self, row: usize, col: usize) -> f64 {
+        assert!(row < self.n && col < self.n);
+        if col > row {
+            0.0
+        } else {
+            self.data[packed_index(row, col)]
+        }
+    }
+
+    // set a value in the matrix, panics if (row, col) is above the
+    // diagonal, or on the diagonal of a unit triangular matrix
+    pub fn set(&mut self, row: usize, col: usize, val: f64) {
+        assert!(row < self.n && col <= row);
+        assert!(!(self.unit && row == col));
+        self.data[packed_index(row, col)] = val;
+    }
+
+    // solve Lx = b with forward substitution, O(n^2).
+    // fails if there is a zero on the diagonal
+    pub fn solve(&self, b: &[f64]) -> Result<Vec<f64>, ()> {
+        assert_eq!(self.n, b.len());
+        let mut x = vec![0.0; self.n];
+        for i in 0..self.n {
+            let d = self.get(i, i);
+            if d == 0.0 {
+                return Err(());
+            }
+            x[i] = (b[i] - (0..i).map(|j| self.get(i, j) * x[j]).sum::<f64>()) / d;
+        }
+        Ok(x)
+    }
+
+    // invert the matrix, only touching the lower triangle, O(n^3 / 6).
+    // fails if there is a zero on the diagonal
+    pub fn invert(&self) -> Result<Self, ()> {
+        let n = self.n;
+        let mut res = LowerTriangular::new(n);
+        for j in 0..n {
+            let d = self.get(j, j);
+            if d == 0.0 {
+                return Err(());
+            }
+            res.set(j, j, 1.0 / d);
+            for i in (j + 1)..n {
+                let v: f64 = (j..i).map(|k| self.get(i, k) * res.get(k, j)).sum();
+                res.set(i, j, -v / self.get(i, i));
+            }
+        }
+        res.unit = self.unit;
+        Ok(res)
+    }
+
+    // construct the transpose of the matrix
+    pub fn transpose(&self) -> UpperTriangular {
+        // the packed layouts mirror each other
+        UpperTriangular {
+            n: self.n,
+            unit: self.unit,
+            data: self.data.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+// upper triangular matrix, packed column by column so only
+// the n * (n + 1) / 2 values on or above the diagonal are stored.
+// unit upper triangular matrices have a diagonal fixed at 1
+pub struct UpperTriangular {
+    n: usize,
+    unit: bool,
+    data: Box<[f64]>,
+}
+
+impl UpperTriangular {
+    // construct a new zero upper triangular matrix
+    pub fn new(n: usize) -> Self {
+        assert!(n > 0);
+        UpperTriangular {
+            n,
+            unit: false,
+            data: vec![0.0; packed_len(n)].into_boxed_slice(),
+        }
+    }
+
+    // construct a new unit upper triangular matrix, with only 1 on the diagonal
+    pub fn new_unit(n: usize) -> Self {
+        LowerTriangular::new_unit(n).transpose()
+    }
+
+    // size of the matrix
+    pub fn size(&self) -> usize {
+        self.n
+    }
+
+    // is the diagonal fixed at 1?
+    pub fn is_unit(&self) -> bool {
+        self.unit
+    }
+
+    // get a value from the matrix
+    pub fn get(&self, row: usize, col: usize) -> f64 {
+        assert!(row < self.n && col < self.n);
+        if row > col {
+            0.0
+        } else {
+            self.data[packed_index(col, row)]
+        }
+    }
+
+    // set a value in the matrix, panics if (row, col) is below the
+    // diagonal, or on the diagonal of a unit triangular matrix
+    pub fn set(&mut self, row: usize, col: usize, val: f64) {
+        assert!(col < self.n && row <= col);
+        assert!(!(self.unit && row == col));
+        self.data[packed_index(col, row)] = val;
+    }
+
+    // solve Ux = b with back substitution, O(n^2).
+    // fails if there is a zero on the diagonal
+    pub fn solve(&self, b: &[f64]) -> Result<Vec<f64>, ()> {
+        assert_eq!(self.n, b.len());
+        let n = self.n;
+        let mut x = vec![0.0; n];
+        for i in (0..n).rev() {
+            let d = self.get(i, i);
+            if d == 0.0 {
+                return Err(());
+            }
+            x[i] = (b[i] - ((i + 1)..n).map(|j| self.get(i, j) * x[j]).sum::<f64>()) / d;
+        }
+        Ok(x)
+    }
+
+    // invert the matrix, only touching the upper triangle, O(n^3 / 6).
+    // fails if there is a zero on the diagonal
+    pub fn invert(&self) -> Result<Self, ()> {
+        self.transpose().invert().map(|l| l.transpose())
+    }
+
+    // construct the transpose of the matrix
+    pub fn transpose(&self) -> LowerTriangular {
+        LowerTriangular {
+            n: self.n,
+            unit: self.unit,
+            data: self.data.clone(),
+        }
+    }
+}
+
+impl From<&Mat> for LowerTriangular {
+    // take the lower triangle of a square matrix
+    fn from(m: &Mat) -> Self {
+        assert!(m.is_square());
+        let mut res = LowerTriangular::new(m.rows());
+        for r in 0..m.rows() {
+            for c in 0..=r {
+                res.set(r, c, m.get(r, c));
+            }
+        }
+        res
+    }
+}
+
+impl From<&Mat> for UpperTriangular {
+    // take the upper triangle of a square matrix
+    fn from(m: &Mat) -> Self {
+        assert!(m.is_square());
+        let mut res = UpperTriangular::new(m.rows());
+        for r in 0..m.rows() {
+            for c in r..m.cols() {
+                res.set(r, c, m.get(r, c));
+            }
+        }
+        res
+    }
+}
+
+impl From<&LowerTriangular> for Mat {
+    fn from(l: &LowerTriangular) -> Self {
+        let mut res = Mat::new(l.n, l.n);
+        for r in 0..l.n {
+            for c in 0..=r {
+                res.set(r, c, l.get(r, c));
+            }
+        }
+        res
+    }
+}
+
+impl From<&UpperTriangular> for Mat {
+    fn from(u: &UpperTriangular) -> Self {
+        let mut res = Mat::new(u.n, u.n);
+        for r in 0..u.n {
+            for c in r..u.n {
+                res.set(r, c, u.get(r, c));
+            }
+        }
+        res
+    }
+}
+
+// lower triangular matrix * vector
+impl Mul<&Vec<f64>> for &LowerTriangular {
+    type Output = Vec<f64>;
+
+    fn mul(self, rhs: &Vec<f64>) -> Self::Output {
+        assert_eq!(self.n, rhs.len());
+        (0..self.n)
+            .map(|r| (0..=r).map(|c| self.get(r, c) * rhs[c]).sum())
+            .collect()
+    }
+}
+
+// upper triangular matrix * vector
+impl Mul<&Vec<f64>> for &UpperTriangular {
+    type Output = Vec<f64>;
+
+    fn mul(self, rhs: &Vec<f64>) -> Self::Output {
+        assert_eq!(self.n, rhs.len());
+        (0..self.n)
+            .map(|r| (r..self.n).map(|c| self.get(r, c) * rhs[c]).sum())
+            .collect()
+    }
+}
+
+// lower triangular matrix * lower triangular matrix
+impl Mul<&LowerTriangular> for &LowerTriangular {
+    type Output = LowerTriangular;
+
+    fn mul(self, rhs: &LowerTriangular) -> Self::Output {
+        assert_eq!(self.n, rhs.n);
+        let mut res = LowerTriangular::new(self.n);
+        for r in 0..self.n {
+            for c in 0..=r {
+                res.set(r, c, (c..=r).map(|k| self.get(r, k) * rhs.get(k, c)).sum());
+            }
+        }
+        res.unit = self.unit && rhs.unit;
+        res
+    }
+}
+
+// upper triangular matrix * upper triangular matrix
+impl Mul<&UpperTriangular> for &UpperTriangular {
+    type Output = UpperTriangular;
+
+    fn mul(self, rhs: &UpperTriangular) -> Self::Output {
+        // (UV)^T = V^T U^T
+        (&rhs.transpose() * &self.transpose()).transpose()
+    }
+}
+
+// lower triangular matrix * upper triangular matrix, as in A = LU
+impl Mul<&UpperTriangular> for &LowerTriangular {
+    type Output = Mat;
+
+    fn mul(self, rhs: &UpperTriangular) -> Self::Output {
+        assert_eq!(self.n, rhs.n);
+        let mut res = Mat::new(self.n, self.n);
+        for r in 0..self.n {
+            for c in 0..self.n {
+                let k_max = usize::min(r, c);
+                res.set(
+                    r,
+                    c,
+                    (0..=k_max).map(|k| self.get(r, k) * rhs.get(k, c)).sum(),
+                );
+            }
+        }
+        res
+    }
+}
+
+// upper triangular matrix * lower triangular matrix, as in A^-1 = U^-1 L^-1
+impl Mul<&LowerTriangular> for &UpperTriangular {
+    type Output = Mat;
+
+    fn mul(self, rhs: &LowerTriangular) -> Self::Output {
+        assert_eq!(self.n, rhs.n);
+        let mut res = Mat::new(self.n, self.n);
+        for r in 0..self.n {
+            for c in 0..self.n {
+                let k_min = usize::max(r, c);
+                res.set(
+                    r,
+                    c,
+                    (k_min..self.n)
+                        .map(|k| self.get(r, k) * rhs.get(k, c))
+                        .sum(),
+                );
+            }
+        }
+        res
+    }
+}
+
+// allow triangular matrices to be printed by println
+impl fmt::Display for LowerTriangular {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Mat::from(self).fmt(f)
+    }
+}
+
+// allow triangular matrices to be printed by println
+impl fmt::Display for UpperTriangular {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Mat::from(self).fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mat_eqn_solver::{back_sub, forward_sub};
+
+    fn mat(n: usize, values: &[f64]) -> Mat {
+        let mut m = Mat::new(n, n);
+        for (i, v) in values.iter().enumerate() {
+            m.set(i / n, i % n, *v);
+        }
+        m
+    }
+
+    fn lower() -> LowerTriangular {
+        LowerTriangular::from(&mat(3, &[2., 0., 0., -1., 4., 0., 3., 0.5, -2.]))
+    }
+
+    fn unit_lower() -> LowerTriangular {
+        let mut l = LowerTriangular::new_unit(3);
+        l.set(1, 0, 0.5);
+        l.set(2, 0, -2.);
+        l.set(2, 1, 3.);
+        l
+    }
+
+    fn assert_close(a: &[f64], b: &[f64]) {
+        assert_eq!(a.len(), b.len());
+        for (x, y) in a.iter().zip(b.iter()) {
+            assert!((x - y).abs() < 1e-12, "{:?} != {:?}", a, b);
+        }
+    }
+
+    fn assert_identity(m: &Mat) {
+        for (r, row) in m.iter().enumerate() {
+            let e: Vec<f64> = (0..m.cols())
+                .map(|c| if r == c { 1. } else { 0. })
+                .collect();
+            assert_close(row, &e);
+        }
+    }
+
+    #[test]
+    fn mat_round_trip() {
+        let m = mat(3, &[1., 2., 3., 4., 5., 6., 7., 8., 9.]);
+        let l = Mat::from(&LowerTriangular::from(&m));
+        let u = Mat::from(&UpperTriangular::from(&m));
+        for r in 0..3 {
+            for c in 0..3 {
+                assert_eq!(l.get(r, c), if c <= r { m.get(r, c) } else { 0. });
+                assert_eq!(u.get(r, c), if c >= r { m.get(r, c) } else { 0. });
+            }
+        }
+        // taking the triangle of a triangular matrix changes nothing
+        let l2 = Mat::from(&LowerTriangular::from(&l));
+        let u2 = Mat::from(&UpperTriangular::from(&u));
+        assert!(l2.iter().eq(l.iter()));
+        assert!(u2.iter().eq(u.iter()));
+    }
+
+    #[test]
+    fn solve_matches_dense_substitution() {
+        let b = [1., -2., 5.];
+        for l in [lower(), unit_lower()].iter() {
+            let dense = Mat::from(l);
+            assert_close(&l.solve(&b).unwrap(), &forward_sub(&dense, &b));
+            let u = l.transpose();
+            assert_close(&u.solve(&b).unwrap(), &back_sub(&Mat::from(&u), &b));
+        }
+    }
+
+    #[test]
+    fn solve_fails_on_zero_diagonal() {
+        let l = LowerTriangular::from(&mat(2, &[1., 0., 3., 0.]));
+        assert!(l.solve(&[1., 1.]).is_err());
+        assert!(l.transpose().solve(&[1., 1.]).is_err());
+    }
+
+    #[test]
+    fn invert_gives_identity() {
+        for l in [lower(), unit_lower()].iter() {
+            let inv = l.invert().unwrap();
+            assert_eq!(inv.is_unit(), l.is_unit());
+            assert_identity(&(&Mat::from(&inv) * &Mat::from(l)));
+
+            let u = l.transpose();
+            let inv = u.invert().unwrap();
+            assert_eq!(inv.is_unit(), u.is_unit());
+            assert_identity(&(&Mat::from(&inv) * &Mat::from(&u)));
+        }
+    }
+}
@@ -14,7 +14,7 @@ impl UpperTriangle for Gaussian {
         assert!(m.rows() >= m.cols());
         let n = m.rows() - 1;
         for i in 0..n {
-            let p = match (i..=n).filter(|p| m.get(*p, i) != 0.0).next() {
+            let p = match (i..=n).find(|p| m.get(*p, i) != 0.0) {
                 Some(p) => p,
                 None => return Err(()),
             };
@@ -39,3 +39,52 @@ impl UpperTriangle for Gaussian {
         Ok(())
     }
 }
+
+// row at or below `row` with the largest magnitude value in column `col`,
+// the first such row if several are equally large
+pub(crate) fn find_pivot(m: &Mat, row: usize, col: usize) -> usize {
+    let mut p = row;
+    for r in (row + 1)..m.rows() {
+        if m.get(r, col).abs() > m.get(p, col).abs() {
+            p = r;
+        }
+    }
+    p
+}
+
+// gaussian elimination with partial pivoting, swapping the row with
+// the largest magnitude value in the column into the pivot position.
+// this keeps every multiplier at most 1 in magnitude, limiting the growth
+// of rounding error
+pub struct PartialPivot;
+
+impl UpperTriangle for PartialPivot {
+    fn run<F: FnMut(&RowOperation)>(m: &mut Mat, h: &mut F) -> Result<(), ()> {
+        assert!(m.rows() >= m.cols());
+        let n = m.rows() - 1;
+        for i in 0..n {
+            let p = find_pivot(m, i, i);
+            if m.get(p, i) == 0.0 {
+                return Err(());
+            }
+            if i != p {
+                let op = RowOperation::Swap(i, p);
+                m.apply(&op);
+                h(&op);
+            }
+            for j in (i + 1)..=n {
+                let op = RowOperation::Cmb {
+                    src: i,
+                    scale: m.get(j, i) / m.get(i, i),
+                    dest: j,
+                };
+                m.apply(&op);
+                h(&op);
+            }
+        }
+        if m.get(n, n) == 0.0 {
+            return Err(());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,220 @@
+use crate::mat::Mat;
+use crate::norm::VecNorm;
+use std::fmt;
+use std::iter::FromIterator;
+use std::ops::{Add, AddAssign, Deref, DerefMut, Mul, MulAssign, Neg, Sub, SubAssign};
+
+#[derive(Debug, Clone, PartialEq)]
+// column vector, represented by an array on the heap
+pub struct Vector {
+    data: Vec<f64>,
+}
+
+impl Vector {
+    // construct a new zero vector
+    pub fn new(len: usize) -> Self {
+        Vector {
+            data: vec![0.0; len],
+        }
+    }
+
+    // construct the i-th standard basis vector of the given length
+    pub fn new_e(len: usize, i: usize) -> Self {
+        let mut res = Vector::new(len);
+        res[i] = 1.0;
+        res
+    }
+
+    // unwrap into the underlying values
+    pub fn into_vec(self) -> Vec<f64> {
+        self.data
+    }
+
+    // dot product with another vector
+    pub fn dot(&self, rhs: &[f64]) -> f64 {
+        assert_eq!(self.len(), rhs.len());
+        self.iter().zip(rhs.iter()).map(|(v1, v2)| *v1 * *v2).sum()
+    }
+
+    // self = self + a * x
+    pub fn axpy(&mut self, a: f64, x: &[f64]) {
+        assert_eq!(self.len(), x.len());
+        for (v, xv) in self.iter_mut().zip(x.iter()) {
+            *v += a * *xv;
+        }
+    }
+
+    // calculate the norm of the vector
+    pub fn norm<N: VecNorm>(&self) -> f64 {
+        N::vec_norm(self)
+    }
+
+    // scale the vector so it has unit norm,
+    // fails if the vector is zero
+    pub fn normalize<N: VecNorm>(&mut self) -> Result<(), ()> {
+        let n = self.norm::<N>();
+        if n == 0.0 || !n.is_finite() {
+            return Err(());
+        }
+        *self *= 1.0 / n;
+        Ok(())
+    }
+}
+
+impl Mat {
+    // calculate the residual b - Ax
+    pub fn residual(&self, x: &[f64], b: &[f64]) -> Vector {
+        assert_eq!(self.cols(), x.len());
+        assert_eq!(self.rows(), b.len());
+        (0..self.rows())
+            .map(|r| {
+                b[r] - self
+                    .iter_row(r)
+                    .zip(x.iter())
+                    .map(|(v1, v2)| *v1 * *v2)
+                    .sum::<f64>()
+            })
+            .collect()
+    }
+}
+
+impl From<Vec<f64>> for Vector {
+    fn from(data: Vec<f64>) -> Self {
+        Vector { data }
+    }
+}
+
+impl From<&[f64]> for Vector {
+    fn from(data: &[f64]) -> Self {
+        Vector {
+            data: data.to_vec(),
+        }
+    }
+}
+
+impl From<Vector> for Vec<f64> {
+    fn from(v: Vector) -> Self {
+        v.data
+    }
+}
+
+impl FromIterator<f64> for Vector {
+    fn from_iter<I: IntoIterator<Item = f64>>(iter: I) -> Self {
+        Vector {
+            data: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Deref for Vector {
+    type Target = [f64];
+
+    fn deref(&self) -> &[f64] {
+        &self.data
+    }
+}
+
+impl DerefMut for Vector {
+    fn deref_mut(&mut self) -> &mut [f64] {
+        &mut self.data
+    }
+}
+
+impl AsRef<[f64]> for Vector {
+    fn as_ref(&self) -> &[f64] {
+        &self.data
+    }
+}
+
+// vector + vector
+impl Add<&Vector> for &Vector {
+    type Output = Vector;
+
+    fn add(self, rhs: &Vector) -> Self::Output {
+        let mut res = self.clone();
+        res += rhs;
+        res
+    }
+}
+
+// vector - vector
+impl Sub<&Vector> for &Vector {
+    type Output = Vector;
+
+    fn sub(self, rhs: &Vector) -> Self::Output {
+        let mut res = self.clone();
+        res -= rhs;
+        res
+    }
+}
+
+// -vector
+impl Neg for &Vector {
+    type Output = Vector;
+
+    fn neg(self) -> Self::Output {
+        self.iter().map(|v| -*v).collect()
+    }
+}
+
+// float * vector
+impl Mul<&Vector> for f64 {
+    type Output = Vector;
+
+    fn mul(self, rhs: &Vector) -> Self::Output {
+        let mut res = rhs.clone();
+        res *= self;
+        res
+    }
+}
+
+// matrix * vector
+impl Mul<&Vector> for &Mat {
+    type Output = Vector;
+
+    fn mul(self, rhs: &Vector) -> Self::Output {
+        assert_eq!(self.cols(), rhs.len());
+        (0..self.rows())
+            .map(|r| {
+                rhs.iter()
+                    .zip(self.iter_row(r))
+                    .map(|(v1, v2)| *v1 * *v2)
+                    .sum()
+            })
+            .collect()
+    }
+}
+
+impl AddAssign<&Vector> for Vector {
+    fn add_assign(&mut self, rhs: &Vector) {
+        self.axpy(1.0, rhs);
+    }
+}
+
+impl SubAssign<&Vector> for Vector {
+    fn sub_assign(&mut self, rhs: &Vector) {
+        self.axpy(-1.0, rhs);
+    }
+}
+
+impl MulAssign<f64> for Vector {
+    fn mul_assign(&mut self, rhs: f64) {
+        for v in self.iter_mut() {
+            *v *= rhs;
+        }
+    }
+}
+
+// allow vectors to be printed by println
+impl fmt::Display for Vector {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[")?;
+        for (i, v) in self.iter().enumerate() {
+            if i != 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", v)?;
+        }
+        write!(f, "]")
+    }
+}